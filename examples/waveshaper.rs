@@ -1,12 +1,16 @@
 extern crate dsp_box;
 
-use dsp_box::{Knob, Range, ZeroDBPos};
+use dsp_box::{Knob, MeterScale, Range, Readout, Scope, Spectrum, TransferCurve, ZeroDBPos};
 
 static ONE_OVER_20: f32 = 1.0 / 20.0;
 #[inline]
 fn db_to_amp(db: f32) -> f32 {
     10.0f32.powf(db * ONE_OVER_20)
 }
+#[inline]
+fn amp_to_db(amp: f32) -> f32 {
+    20.0 * amp.max(1e-6).log10()
+}
 
 struct Waveshaper {
     prev_in_gain_db: f32,
@@ -57,6 +61,13 @@ impl dsp_box::DSPBoxApp for Waveshaper {
 
             *smp = Self::waveshape_distort(tmp_smp) * self.out_gain_amp;
         }
+
+        let peak = proc_info
+            .out_l
+            .iter()
+            .chain(proc_info.out_r.iter())
+            .fold(0.0f32, |peak, smp| peak.max(smp.abs()));
+        proc_info.out_values.publish("Output Peak", amp_to_db(peak));
     }
 }
 
@@ -64,12 +75,14 @@ pub fn main() {
     let mut gui_setup = dsp_box::GuiSetup::new("Waveshaper - DSP Box");
 
     gui_setup.load_audio_file("audio_files/sine_c4.wav", 1.2);
+    gui_setup.remember_session();
 
     gui_setup.push_knob(Knob {
         label: "Input Gain",
         value: 0.0,
         default_value: 0.0,
         range: Range::db(-24.0, 24.0, ZeroDBPos::Center),
+        ..Default::default()
     });
 
     gui_setup.push_knob(Knob {
@@ -77,7 +90,43 @@ pub fn main() {
         value: -6.0,
         default_value: 0.0,
         range: Range::db(-24.0, 24.0, ZeroDBPos::Center),
+        skip_randomize: true,
+        ..Default::default()
+    });
+
+    gui_setup.push_readout(Readout {
+        label: "Output Peak",
+        range: Range::db(-48.0, 0.0, ZeroDBPos::Max),
+        ..Default::default()
+    });
+
+    gui_setup.push_transfer_curve(TransferCurve {
+        label: "Curve",
+        curve: Box::new(|x, params| {
+            let shaped = Waveshaper::waveshape_distort(x * db_to_amp(params[0]));
+            shaped * db_to_amp(params[1])
+        }),
+        show_level: true,
+        ..Default::default()
     });
 
+    gui_setup.push_scope(Scope {
+        label: "Scope",
+        ..Default::default()
+    });
+
+    gui_setup.push_spectrum(Spectrum {
+        label: "Spectrum",
+        ..Default::default()
+    });
+
+    gui_setup.show_top_bar_meters(MeterScale::DB60);
+
+    // e.g. `cargo run --example waveshaper -- --param "Input Gain=6" --autoplay`
+    if let Err(e) = gui_setup.parse_args() {
+        eprintln!("{}", e);
+        std::process::exit(1);
+    }
+
     dsp_box::run(Box::new(Waveshaper::new()), gui_setup);
 }
@@ -138,7 +138,8 @@ impl AudioPlayer {
         self.transport = 0;
     }
 
-    pub fn get_next(&mut self, num_frames: usize) -> Option<(&[f32], &[f32])> {
+    /// Returns the next block of audio and the frame it starts at.
+    pub fn get_next(&mut self, num_frames: usize) -> Option<(usize, &[f32], &[f32])> {
         if self.playing {
             if let Some(buffer) = &self.buffer {
                 assert!(buffer.data_l.len() >= num_frames);
@@ -151,6 +152,7 @@ impl AudioPlayer {
                 self.transport += num_frames;
 
                 Some((
+                    transport,
                     &buffer.data_l[transport..self.transport],
                     &buffer.data_r[transport..self.transport],
                 ))
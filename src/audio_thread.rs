@@ -1,12 +1,39 @@
-use crate::DSPBoxApp;
+use crate::automation::{self, AutomationPoint};
+use crate::message_ring::MessageRing;
+use crate::meters::{self, Levels};
+use crate::modulation::{ModulatedNormals, Modulation, Modulator};
+use crate::output_values::OutputValues;
+use crate::scope::ScopeBuffer;
+use crate::shared_state::SharedState;
+use crate::{DSPBoxApp, Range};
 use std::marker::Send;
-use std::sync::mpsc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{mpsc, Arc};
 
 use crate::audio_player::{AudioPlayer, AudioPlayerBuffer};
 
+// automation of a parameter is not played back for this long after the GUI moved it
+static AUTOMATION_TOUCH_SECS: f32 = 0.3;
+
+/// How many retired messages can wait for the GUI to drop them.
+pub(crate) static RETIRED_CAPACITY: usize = 256;
+
+// the block size used when an app is run outside of the audio thread
+static OFFLINE_BLOCK_FRAMES: usize = 256;
+
 pub enum GuiToAudioMsg {
     ParamChanged(Param),
     InitPreset(Vec<Param>),
+    LoadParams(Vec<Param>),
+    SetModulation {
+        id: u32,
+        modulation: Option<Modulation>,
+        range: Range,
+    },
+    SetAutomationLane {
+        id: u32,
+        points: Vec<AutomationPoint>,
+    },
     LoadAudioPlayerBuffer(AudioPlayerBuffer),
     Play,
     Pause,
@@ -14,6 +41,22 @@ pub enum GuiToAudioMsg {
     Bypass,
     Unbypass,
     Panic,
+    Trigger(usize),
+}
+
+#[derive(Copy, Clone)]
+pub enum AudioToGuiMsg {
+    MidiCC { channel: u8, cc: u8, value: u8 },
+    ParamChanged(Param),
+}
+
+/// Memory the audio thread is done with. It is sent back so it is freed on the
+/// GUI thread instead of in the audio callback.
+// the contents are only ever dropped, never read
+#[allow(dead_code)]
+pub(crate) enum Retired {
+    AutomationLane(Vec<AutomationPoint>),
+    Params(Vec<Param>),
 }
 
 #[derive(Copy, Clone)]
@@ -28,15 +71,62 @@ pub struct AudioProcessInfo<'a> {
     pub out_l: &'a mut [f32],
     pub out_r: &'a mut [f32],
     pub sample_rate: f32,
-    pub in_params: &'a Vec<f32>,
+    /// The current parameter values, including any modulation.
+    pub in_params: &'a [f32],
+    /// Values to display in the GUI, e.g.
+    /// `proc_info.out_values.publish("Gain Reduction", gain_reduction_db)`.
+    pub out_values: &'a OutputValues,
+    // the latest value set for each parameter in this block, by id
+    param_changes: &'a mut [Option<f32>],
+    trigger_names: &'a [&'static str],
+    pending_triggers: &'a [bool],
+}
+
+impl<'a> AudioProcessInfo<'a> {
+    /// Sets a parameter from the DSP, e.g. an auto-gain setting the output
+    /// level. The new value is used from the next block on, and the GUI moves
+    /// the parameter's block to match. Only the last value set for a parameter
+    /// in a block is used.
+    pub fn set_param(&mut self, id: u32, value: f32) {
+        if let Some(change) = self.param_changes.get_mut(id as usize) {
+            *change = Some(value);
+        }
+    }
+
+    /// Returns `true` if the trigger with the given name was pressed since the
+    /// last block, e.g. `if proc_info.triggered("Reset Phase") { ... }`. Each
+    /// press is reported in exactly one block, which is the first block after
+    /// bypass ends if it was pressed while bypassed.
+    pub fn triggered(&self, name: &str) -> bool {
+        match self.trigger_names.iter().position(|n| *n == name) {
+            Some(index) => self.pending_triggers[index],
+            None => false,
+        }
+    }
 }
 
 pub struct AudioThread {
     sample_rate: f32,
     dsp_app: Box<dyn DSPBoxApp + Send>,
     gui_to_audio_rx: mpsc::Receiver<GuiToAudioMsg>,
+    audio_to_gui: Arc<MessageRing>,
     host_did_reset: bool,
     in_params: Vec<f32>,
+    modulated_params: Vec<f32>,
+    param_changes: Vec<Option<f32>>,
+    modulators: Vec<Option<Modulator>>,
+    modulated_normals: Arc<ModulatedNormals>,
+    automation_lanes: Vec<Vec<AutomationPoint>>,
+    retired_tx: mpsc::SyncSender<Retired>,
+    automation_touch_frames: Vec<usize>,
+    transport_pos: Arc<AtomicUsize>,
+    output_values: Arc<OutputValues>,
+    trigger_names: Vec<&'static str>,
+    pending_triggers: Vec<bool>,
+    levels: Arc<Levels>,
+    rms_coeff: f32,
+    mean_squares: [f32; 4],
+    scope: Arc<ScopeBuffer>,
     audio_player: AudioPlayer,
     did_init_preset: bool,
     bypassed: bool,
@@ -46,13 +136,33 @@ impl AudioThread {
     pub fn new(
         dsp_app: Box<dyn DSPBoxApp + Send>,
         gui_to_audio_rx: mpsc::Receiver<GuiToAudioMsg>,
+        trigger_names: Vec<&'static str>,
+        shared: SharedState,
     ) -> Self {
+        let pending_triggers = vec![false; trigger_names.len()];
+
         Self {
             sample_rate: 0.0,
             dsp_app,
             gui_to_audio_rx,
+            audio_to_gui: shared.audio_to_gui,
             host_did_reset: false,
             in_params: Vec::new(),
+            modulated_params: Vec::new(),
+            param_changes: Vec::new(),
+            modulators: Vec::new(),
+            modulated_normals: shared.modulated_normals,
+            automation_lanes: Vec::new(),
+            retired_tx: shared.retired_tx,
+            automation_touch_frames: Vec::new(),
+            transport_pos: shared.transport_pos,
+            output_values: shared.output_values,
+            trigger_names,
+            pending_triggers,
+            levels: shared.levels,
+            rms_coeff: 0.0,
+            mean_squares: [0.0; 4],
+            scope: shared.scope,
             audio_player: AudioPlayer::new(),
             did_init_preset: false,
             bypassed: false,
@@ -62,9 +172,23 @@ impl AudioThread {
     // called when host info has changed
     pub fn host_reset(&mut self, sample_rate: f32) {
         self.sample_rate = sample_rate;
+        self.rms_coeff = (-1.0 / (meters::RMS_WINDOW_SECS * sample_rate)).exp();
+        self.scope.set_sample_rate(sample_rate);
         self.host_did_reset = true;
     }
 
+    // called for each incoming raw MIDI message (from jack_thread.rs)
+    pub fn midi_event(&mut self, bytes: &[u8]) {
+        if bytes.len() >= 3 && bytes[0] & 0xF0 == 0xB0 {
+            // dropped if the GUI falls behind
+            self.audio_to_gui.push(AudioToGuiMsg::MidiCC {
+                channel: bytes[0] & 0x0F,
+                cc: bytes[1] & 0x7F,
+                value: bytes[2] & 0x7F,
+            });
+        }
+    }
+
     // the main audio processing function (called from jack_thread.rs)
     pub fn process_audio_stereo<'a>(
         &mut self,
@@ -90,10 +214,49 @@ impl AudioThread {
 
             if self.did_init_preset {
                 let (in_l, in_r) = match self.audio_player.get_next(num_frames) {
-                    Some(data) => data,
+                    Some((frame, data_l, data_r)) => {
+                        self.transport_pos.store(frame, Ordering::Relaxed);
+
+                        // play back automation recorded against the audio file
+                        for (id, lane) in self.automation_lanes.iter().enumerate() {
+                            let touch_frames = &mut self.automation_touch_frames[id];
+                            if *touch_frames > 0 {
+                                *touch_frames = touch_frames.saturating_sub(num_frames);
+                            } else if let Some(value) = automation::value_at(lane, frame) {
+                                self.in_params[id] = value;
+                            }
+                        }
+
+                        (data_l, data_r)
+                    }
                     None => (in_l, in_r),
                 };
 
+                self.levels.measure(
+                    meters::IN_L,
+                    in_l,
+                    self.rms_coeff,
+                    &mut self.mean_squares[meters::IN_L],
+                );
+                self.levels.measure(
+                    meters::IN_R,
+                    in_r,
+                    self.rms_coeff,
+                    &mut self.mean_squares[meters::IN_R],
+                );
+
+                // apply modulation on top of the values set in the GUI
+                self.modulated_params.copy_from_slice(&self.in_params);
+
+                for (id, modulator) in self.modulators.iter_mut().enumerate() {
+                    if let Some(modulator) = modulator {
+                        let normal =
+                            modulator.process(self.in_params[id], in_l, in_r, self.sample_rate);
+                        self.modulated_params[id] = modulator.range().to_value(normal);
+                        self.modulated_normals.set(id, normal.value());
+                    }
+                }
+
                 if self.bypassed {
                     out_l.copy_from_slice(in_l);
                     out_r.copy_from_slice(in_r);
@@ -104,20 +267,73 @@ impl AudioThread {
                         out_l,
                         out_r,
                         sample_rate: self.sample_rate,
-                        in_params: &self.in_params,
+                        in_params: &self.modulated_params,
+                        out_values: &self.output_values,
+                        param_changes: &mut self.param_changes,
+                        trigger_names: &self.trigger_names,
+                        pending_triggers: &self.pending_triggers,
                     };
 
                     self.dsp_app.process_stereo(&mut proc_info);
+
+                    self.apply_param_changes();
+
+                    // triggers pressed while bypassed stay pending until the app runs
+                    for pending in self.pending_triggers.iter_mut() {
+                        *pending = false;
+                    }
+                }
+
+                self.levels.measure(
+                    meters::OUT_L,
+                    out_l,
+                    self.rms_coeff,
+                    &mut self.mean_squares[meters::OUT_L],
+                );
+                self.levels.measure(
+                    meters::OUT_R,
+                    out_r,
+                    self.rms_coeff,
+                    &mut self.mean_squares[meters::OUT_R],
+                );
+
+                self.scope.write(in_l, in_r, out_l, out_r);
+            }
+        }
+    }
+
+    // applies the parameters the DSP set and lets the GUI follow
+    fn apply_param_changes(&mut self) {
+        for (id, change) in self.param_changes.iter_mut().enumerate() {
+            if let Some(value) = change.take() {
+                // e.g. from an unstable filter, which would leave the parameter stuck
+                if !value.is_finite() {
+                    continue;
                 }
+
+                self.in_params[id] = value;
+
+                self.audio_to_gui.push(AudioToGuiMsg::ParamChanged(Param {
+                    id: id as u32,
+                    value,
+                }));
             }
         }
     }
 
+    fn retire(&self, retired: Retired) {
+        // the channel is bounded, so this never allocates. It is only full if the
+        // GUI stopped polling, and then the memory is freed here after all.
+        let _ = self.retired_tx.try_send(retired);
+    }
+
     fn poll_gui_messages(&mut self) {
         for msg in self.gui_to_audio_rx.try_iter() {
             match msg {
                 GuiToAudioMsg::ParamChanged(param) => {
                     self.in_params[param.id as usize] = param.value;
+                    self.automation_touch_frames[param.id as usize] =
+                        (AUTOMATION_TOUCH_SECS * self.sample_rate) as usize;
                 }
                 GuiToAudioMsg::InitPreset(params) => {
                     self.in_params.clear();
@@ -125,8 +341,41 @@ impl AudioThread {
                     for param in params {
                         self.in_params.push(param.value);
                     }
+                    self.modulated_params = self.in_params.clone();
+                    self.param_changes = vec![None; self.in_params.len()];
+                    self.modulators.clear();
+                    self.modulators.resize_with(self.in_params.len(), || None);
+                    self.automation_lanes = vec![Vec::new(); self.in_params.len()];
+                    self.automation_touch_frames = vec![0; self.in_params.len()];
                     self.did_init_preset = true;
                 }
+                GuiToAudioMsg::LoadParams(params) => {
+                    for param in params.iter() {
+                        self.in_params[param.id as usize] = param.value;
+                    }
+                    self.retire(Retired::Params(params));
+                }
+                GuiToAudioMsg::SetModulation {
+                    id,
+                    modulation,
+                    range,
+                } => {
+                    // keep the phase and the envelope of a running route
+                    let slot = &mut self.modulators[id as usize];
+                    match (slot.as_mut(), modulation) {
+                        (Some(modulator), Some(modulation)) => {
+                            modulator.set_modulation(modulation, range)
+                        }
+                        (_, modulation) => {
+                            *slot = modulation.map(|modulation| Modulator::new(modulation, range))
+                        }
+                    }
+                }
+                GuiToAudioMsg::SetAutomationLane { id, points } => {
+                    let old_points =
+                        std::mem::replace(&mut self.automation_lanes[id as usize], points);
+                    self.retire(Retired::AutomationLane(old_points));
+                }
                 GuiToAudioMsg::LoadAudioPlayerBuffer(buffer) => {
                     self.audio_player.load_buffer(buffer);
                 }
@@ -138,6 +387,7 @@ impl AudioThread {
                 }
                 GuiToAudioMsg::Stop => {
                     self.audio_player.stop();
+                    self.transport_pos.store(0, Ordering::Relaxed);
                 }
                 GuiToAudioMsg::Bypass => {
                     self.bypassed = true;
@@ -146,7 +396,50 @@ impl AudioThread {
                     self.bypassed = false;
                 }
                 GuiToAudioMsg::Panic => {}
+                GuiToAudioMsg::Trigger(index) => {
+                    self.pending_triggers[index] = true;
+                }
             }
         }
     }
 }
+
+/// Runs a separate instance of an app over the input outside of the audio
+/// thread, e.g. to measure its response, and returns the left and right output.
+/// Parameter changes the app requests and triggers are ignored.
+pub(crate) fn process_offline(
+    dsp_app: &mut dyn DSPBoxApp,
+    in_l: &[f32],
+    in_r: &[f32],
+    in_params: &[f32],
+    sample_rate: f32,
+) -> (Vec<f32>, Vec<f32>) {
+    let mut out_l = vec![0.0; in_l.len()];
+    let mut out_r = vec![0.0; in_r.len()];
+    let out_values = OutputValues::new(Vec::new());
+
+    dsp_app.host_reset(sample_rate);
+
+    let mut start = 0;
+    while start < in_l.len() {
+        let end = (start + OFFLINE_BLOCK_FRAMES).min(in_l.len());
+
+        let mut proc_info = AudioProcessInfo {
+            in_l: &in_l[start..end],
+            in_r: &in_r[start..end],
+            out_l: &mut out_l[start..end],
+            out_r: &mut out_r[start..end],
+            sample_rate,
+            in_params,
+            out_values: &out_values,
+            param_changes: &mut [],
+            trigger_names: &[],
+            pending_triggers: &[],
+        };
+        dsp_app.process_stereo(&mut proc_info);
+
+        start = end;
+    }
+
+    (out_l, out_r)
+}
@@ -0,0 +1,219 @@
+use std::time::{Duration, Instant};
+
+// a parameter counts as touched for this long after its last move while recording
+static TOUCH_TIME: Duration = Duration::from_millis(300);
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct AutomationPoint {
+    /// The position in the audio file in frames.
+    pub frame: usize,
+    pub value: f32,
+}
+
+/// The value of a parameter over the length of the audio file. Points are
+/// kept sorted by frame.
+#[derive(Debug, Clone, Default)]
+pub struct AutomationLane {
+    points: Vec<AutomationPoint>,
+}
+
+impl AutomationLane {
+    pub fn new() -> Self {
+        Self { points: Vec::new() }
+    }
+
+    pub fn from_points(mut points: Vec<AutomationPoint>) -> Self {
+        points.sort_by_key(|point| point.frame);
+        Self { points }
+    }
+
+    pub fn points(&self) -> &[AutomationPoint] {
+        &self.points
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.points.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.points.clear();
+    }
+
+    /// Returns the value of the last point at or before `frame`. Before the
+    /// first point the value of the first point is used, so a lane holds its
+    /// value when the file loops around.
+    pub fn value_at(&self, frame: usize) -> Option<f32> {
+        value_at(&self.points, frame)
+    }
+
+    /// Records a value, replacing any points from `erase_from` up to `frame`
+    /// that were recorded on a previous loop.
+    pub fn record(&mut self, erase_from: usize, frame: usize, value: f32) {
+        self.points
+            .retain(|point| point.frame < erase_from || point.frame > frame);
+
+        let index = self
+            .points
+            .iter()
+            .position(|point| point.frame > frame)
+            .unwrap_or(self.points.len());
+        self.points.insert(index, AutomationPoint { frame, value });
+    }
+}
+
+/// Returns the value of the last point at or before `frame` of a sorted list
+/// of points.
+pub fn value_at(points: &[AutomationPoint], frame: usize) -> Option<f32> {
+    let first = points.first()?;
+
+    let value = match points.binary_search_by_key(&frame, |point| point.frame) {
+        Ok(index) => points[index].value,
+        Err(0) => first.value,
+        Err(index) => points[index - 1].value,
+    };
+
+    Some(value)
+}
+
+struct Touch {
+    id: u32,
+    last_frame: usize,
+    last_move: Instant,
+}
+
+/// Automation lanes for all parameters, indexed by parameter id.
+pub struct Automation {
+    lanes: Vec<AutomationLane>,
+    armed: bool,
+    touches: Vec<Touch>,
+}
+
+impl Automation {
+    pub fn new(num_params: usize) -> Self {
+        Self {
+            lanes: vec![AutomationLane::new(); num_params],
+            armed: false,
+            touches: Vec::new(),
+        }
+    }
+
+    pub fn armed(&self) -> bool {
+        self.armed
+    }
+
+    pub fn set_armed(&mut self, armed: bool) {
+        self.armed = armed;
+        self.touches.clear();
+    }
+
+    pub fn lane(&self, id: u32) -> &AutomationLane {
+        &self.lanes[id as usize]
+    }
+
+    pub fn lanes(&self) -> &[AutomationLane] {
+        &self.lanes
+    }
+
+    pub fn set_lane(&mut self, id: u32, lane: AutomationLane) {
+        self.lanes[id as usize] = lane;
+    }
+
+    pub fn clear_lane(&mut self, id: u32) {
+        self.lanes[id as usize].clear();
+    }
+
+    /// Records a moved parameter at the given transport position.
+    pub fn record(&mut self, id: u32, value: f32, frame: usize, now: Instant) {
+        self.touches
+            .retain(|touch| now.duration_since(touch.last_move) < TOUCH_TIME);
+
+        let erase_from = match self.touches.iter_mut().find(|touch| touch.id == id) {
+            Some(touch) => {
+                // start over when the file has looped around
+                let erase_from = if touch.last_frame < frame {
+                    touch.last_frame + 1
+                } else {
+                    frame
+                };
+
+                touch.last_frame = frame;
+                touch.last_move = now;
+
+                erase_from
+            }
+            None => {
+                self.touches.push(Touch {
+                    id,
+                    last_frame: frame,
+                    last_move: now,
+                });
+
+                frame
+            }
+        };
+
+        self.lanes[id as usize].record(erase_from, frame, value);
+    }
+
+    /// Returns true if the parameter is currently being moved by the user
+    /// while recording, in which case its lane should not be played back.
+    pub fn is_touched(&self, id: u32, now: Instant) -> bool {
+        self.touches
+            .iter()
+            .any(|touch| touch.id == id && now.duration_since(touch.last_move) < TOUCH_TIME)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn point(frame: usize, value: f32) -> AutomationPoint {
+        AutomationPoint { frame, value }
+    }
+
+    #[test]
+    fn value_at_holds_the_last_point() {
+        let points = [point(100, 0.1), point(200, 0.2), point(300, 0.3)];
+
+        assert_eq!(value_at(&[], 100), None);
+        assert_eq!(value_at(&points, 0), Some(0.1));
+        assert_eq!(value_at(&points, 100), Some(0.1));
+        assert_eq!(value_at(&points, 199), Some(0.1));
+        assert_eq!(value_at(&points, 200), Some(0.2));
+        assert_eq!(value_at(&points, 1000), Some(0.3));
+    }
+
+    #[test]
+    fn lane_record_replaces_points_in_range() {
+        let mut lane =
+            AutomationLane::from_points(vec![point(300, 0.3), point(100, 0.1), point(200, 0.2)]);
+        lane.record(150, 250, 0.5);
+
+        assert_eq!(
+            lane.points(),
+            &[point(100, 0.1), point(250, 0.5), point(300, 0.3)]
+        );
+    }
+
+    #[test]
+    fn touched_param_erases_since_its_last_move() {
+        let mut automation = Automation::new(1);
+        automation.set_lane(
+            0,
+            AutomationLane::from_points(vec![point(100, 0.1), point(150, 0.2), point(200, 0.3)]),
+        );
+
+        let now = Instant::now();
+        let later = now + Duration::from_millis(10);
+        automation.record(0, 0.5, 100, now);
+        automation.record(0, 0.6, 200, later);
+
+        assert_eq!(
+            automation.lane(0).points(),
+            &[point(100, 0.5), point(200, 0.6)]
+        );
+        assert!(automation.is_touched(0, later));
+        assert!(!automation.is_touched(0, later + TOUCH_TIME));
+    }
+}
@@ -0,0 +1,73 @@
+use iced_native::{layout, Clipboard, Element, Event, Hasher, Layout, Length, Point, Widget};
+
+/// Wraps an element and draws it without passing any events to it, so it can't
+/// be interacted with.
+pub struct DisabledArea<'a, Message, Renderer> {
+    content: Element<'a, Message, Renderer>,
+}
+
+impl<'a, Message, Renderer> DisabledArea<'a, Message, Renderer> {
+    pub fn new<T>(content: T) -> Self
+    where
+        T: Into<Element<'a, Message, Renderer>>,
+    {
+        Self {
+            content: content.into(),
+        }
+    }
+}
+
+impl<'a, Message, Renderer> Widget<Message, Renderer> for DisabledArea<'a, Message, Renderer>
+where
+    Renderer: iced_native::Renderer,
+{
+    fn width(&self) -> Length {
+        self.content.width()
+    }
+
+    fn height(&self) -> Length {
+        self.content.height()
+    }
+
+    fn layout(&self, renderer: &Renderer, limits: &layout::Limits) -> layout::Node {
+        self.content.layout(renderer, limits)
+    }
+
+    fn draw(
+        &self,
+        renderer: &mut Renderer,
+        defaults: &Renderer::Defaults,
+        layout: Layout<'_>,
+        _cursor_position: Point,
+    ) -> Renderer::Output {
+        // a cursor outside of the window, so nothing is drawn as hovered
+        self.content
+            .draw(renderer, defaults, layout, Point::new(-1.0, -1.0))
+    }
+
+    fn hash_layout(&self, state: &mut Hasher) {
+        self.content.hash_layout(state);
+    }
+
+    fn on_event(
+        &mut self,
+        _event: Event,
+        _layout: Layout<'_>,
+        _cursor_position: Point,
+        _messages: &mut Vec<Message>,
+        _renderer: &Renderer,
+        _clipboard: Option<&dyn Clipboard>,
+    ) {
+    }
+}
+
+impl<'a, Message, Renderer> From<DisabledArea<'a, Message, Renderer>>
+    for Element<'a, Message, Renderer>
+where
+    Message: 'a,
+    Renderer: 'a + iced_native::Renderer,
+{
+    fn from(area: DisabledArea<'a, Message, Renderer>) -> Element<'a, Message, Renderer> {
+        Element::new(area)
+    }
+}
@@ -0,0 +1,113 @@
+use iced_native::{
+    layout, mouse, Clipboard, Element, Event, Hasher, Layout, Length, Point, Widget,
+};
+
+use std::time::{Duration, Instant};
+
+// the longest time between two clicks that still counts as a double-click
+static DOUBLE_CLICK_TIME: Duration = Duration::from_millis(300);
+
+/// Wraps an element and produces a message when it is double-clicked. All
+/// events except the second click are passed through to the wrapped element,
+/// so e.g. a pad doesn't start a drag that undoes a reset.
+pub struct DoubleClickArea<'a, Message, Renderer> {
+    content: Element<'a, Message, Renderer>,
+    last_click: &'a mut Option<Instant>,
+    on_double_click: Message,
+}
+
+impl<'a, Message, Renderer> DoubleClickArea<'a, Message, Renderer> {
+    /// `last_click` keeps the time of the previous click between views.
+    pub fn new<T>(content: T, last_click: &'a mut Option<Instant>, on_double_click: Message) -> Self
+    where
+        T: Into<Element<'a, Message, Renderer>>,
+    {
+        Self {
+            content: content.into(),
+            last_click,
+            on_double_click,
+        }
+    }
+}
+
+impl<'a, Message, Renderer> Widget<Message, Renderer> for DoubleClickArea<'a, Message, Renderer>
+where
+    Message: Clone,
+    Renderer: iced_native::Renderer,
+{
+    fn width(&self) -> Length {
+        self.content.width()
+    }
+
+    fn height(&self) -> Length {
+        self.content.height()
+    }
+
+    fn layout(&self, renderer: &Renderer, limits: &layout::Limits) -> layout::Node {
+        self.content.layout(renderer, limits)
+    }
+
+    fn draw(
+        &self,
+        renderer: &mut Renderer,
+        defaults: &Renderer::Defaults,
+        layout: Layout<'_>,
+        cursor_position: Point,
+    ) -> Renderer::Output {
+        self.content
+            .draw(renderer, defaults, layout, cursor_position)
+    }
+
+    fn hash_layout(&self, state: &mut Hasher) {
+        self.content.hash_layout(state);
+    }
+
+    fn on_event(
+        &mut self,
+        event: Event,
+        layout: Layout<'_>,
+        cursor_position: Point,
+        messages: &mut Vec<Message>,
+        renderer: &Renderer,
+        clipboard: Option<&dyn Clipboard>,
+    ) {
+        let is_click = match event {
+            Event::Mouse(mouse::Event::ButtonPressed(mouse::Button::Left)) => {
+                layout.bounds().contains(cursor_position)
+            }
+            _ => false,
+        };
+
+        if is_click {
+            let now = Instant::now();
+
+            match self.last_click.take() {
+                Some(last_click) if now.duration_since(last_click) < DOUBLE_CLICK_TIME => {
+                    messages.push(self.on_double_click.clone());
+                    return;
+                }
+                _ => *self.last_click = Some(now),
+            }
+        }
+
+        self.content.on_event(
+            event,
+            layout,
+            cursor_position,
+            messages,
+            renderer,
+            clipboard,
+        );
+    }
+}
+
+impl<'a, Message, Renderer> From<DoubleClickArea<'a, Message, Renderer>>
+    for Element<'a, Message, Renderer>
+where
+    Message: 'a + Clone,
+    Renderer: 'a + iced_native::Renderer,
+{
+    fn from(area: DoubleClickArea<'a, Message, Renderer>) -> Element<'a, Message, Renderer> {
+        Element::new(area)
+    }
+}
@@ -0,0 +1,181 @@
+use crate::blocks::plot::{db_grid, log_freq_grid, normal_to_freq, GRID_DB};
+use crate::blocks::{Plot, Trace};
+use crate::response::{self, AppFactory, Measurement, ResponseSource};
+use crate::{blocks, style, Complex, FrequencyResponse, Message};
+
+use iced::{Align, Canvas, Column, Container, Element, Length, Text};
+
+use std::f32::consts::PI;
+use std::sync::{mpsc, Arc};
+
+// the number of frequencies the response is shown at
+static NUM_POINTS: usize = 256;
+
+enum Source {
+    Analytic(Box<dyn Fn(f32, &[f32]) -> Complex<f32>>),
+    Measured(AppFactory),
+}
+
+/// Plots the magnitude and optionally the phase of a frequency response.
+pub struct FrequencyResponseBlock {
+    pub label: String,
+    source: Source,
+    show_phase: bool,
+    min_db: f32,
+    max_db: f32,
+    // the parameter values the response was last shown or measured with
+    params: Option<Vec<f32>>,
+    // set when the parameters changed since the response was last shown or
+    // measured
+    stale: bool,
+    // a measurement that is running in the background
+    pending: Option<mpsc::Receiver<Measurement>>,
+    plot: Plot,
+}
+
+impl FrequencyResponseBlock {
+    pub fn new(frequency_response: FrequencyResponse) -> Self {
+        let min_db = frequency_response
+            .min_db
+            .min(frequency_response.max_db - GRID_DB);
+        let max_db = frequency_response.max_db;
+
+        let mut plot = Plot::new();
+        plot.set_grid(log_freq_grid(), db_grid(min_db, max_db));
+
+        Self {
+            label: String::from(frequency_response.label),
+            source: match frequency_response.source {
+                ResponseSource::Analytic(response) => Source::Analytic(response),
+                ResponseSource::Measured(factory) => Source::Measured(Arc::from(factory)),
+            },
+            show_phase: frequency_response.show_phase,
+            min_db,
+            max_db,
+            params: None,
+            stale: true,
+            pending: None,
+            plot,
+        }
+    }
+
+    /// Shows the response again if a parameter changed. A measured response is
+    /// started in the background and shown on a later update once it is done.
+    pub fn update_response(&mut self, params: &[f32], sample_rate: f32) {
+        if self.params.as_deref() != Some(params) {
+            self.params = Some(params.to_vec());
+            self.stale = true;
+        }
+
+        let min_db = self.min_db;
+        let max_db = self.max_db;
+        let show_phase = self.show_phase;
+
+        match &self.source {
+            Source::Analytic(response) => {
+                if self.stale {
+                    self.plot.set_traces(traces(
+                        |freq| response(freq, params),
+                        min_db,
+                        max_db,
+                        show_phase,
+                    ));
+                    self.stale = false;
+                }
+            }
+            Source::Measured(factory) => {
+                if let Some(pending) = &self.pending {
+                    match pending.try_recv() {
+                        Ok(measurement) => {
+                            self.plot.set_traces(traces(
+                                |freq| measurement.at(freq),
+                                min_db,
+                                max_db,
+                                show_phase,
+                            ));
+                            self.pending = None;
+                        }
+                        Err(mpsc::TryRecvError::Empty) => return,
+                        Err(mpsc::TryRecvError::Disconnected) => {
+                            eprintln!("Failed to measure the frequency response of {}", self.label);
+                            self.pending = None;
+                        }
+                    }
+                }
+
+                // only one measurement runs at a time, the latest parameters are
+                // measured once it is done
+                if self.stale && sample_rate != 0.0 {
+                    self.pending = Some(response::measure(
+                        Arc::clone(factory),
+                        params.to_vec(),
+                        sample_rate,
+                    ));
+                    self.stale = false;
+                }
+            }
+        }
+    }
+
+    pub fn view(&mut self, style: &style::Theme, enabled: bool) -> Element<Message> {
+        self.plot.set_style(style.plot());
+        let canvas = Canvas::new(&mut self.plot)
+            .width(Length::Fill)
+            .height(Length::Fill);
+
+        let column = Column::new()
+            .width(Length::from(blocks::BLOCK_WIDTH * 4))
+            .height(Length::from(blocks::BLOCK_HEIGHT))
+            .align_items(Align::Center)
+            .padding(6)
+            .spacing(4)
+            .push(canvas)
+            .push(Text::new(&self.label).size(12));
+
+        Container::new(column)
+            .style(if enabled {
+                style.top_bar_container()
+            } else {
+                style.disabled_block_container()
+            })
+            .into()
+    }
+}
+
+// the magnitude in the primary color and the phase in the secondary one
+fn traces<F: Fn(f32) -> Complex<f32>>(
+    response: F,
+    min_db: f32,
+    max_db: f32,
+    show_phase: bool,
+) -> Vec<Trace> {
+    let responses: Vec<(f32, Complex<f32>)> = (0..NUM_POINTS)
+        .map(|i| {
+            let normal = i as f32 / (NUM_POINTS - 1) as f32;
+            (normal, response(normal_to_freq(normal)))
+        })
+        .collect();
+
+    let mut traces = vec![Trace {
+        points: responses
+            .iter()
+            .map(|(x, response)| {
+                let db = 20.0 * response.norm().max(1e-7).log10();
+                (*x, (db - min_db) / (max_db - min_db))
+            })
+            .collect(),
+        secondary: false,
+    }];
+
+    if show_phase {
+        traces.push(Trace {
+            points: responses
+                .iter()
+                .map(|(x, response)| (*x, (response.arg() + PI) / (2.0 * PI)))
+                .collect(),
+            secondary: true,
+        });
+    }
+
+    traces
+}
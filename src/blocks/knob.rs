@@ -1,29 +1,133 @@
-use crate::{blocks, style, GuiToAudioMsg, Message, Param, Range};
+use crate::blocks::{BlockMenu, DisabledArea, RightClickArea, ValueFormat};
+use crate::modulation::{ModulatedNormals, Modulation};
+use crate::{blocks, style, GuiToAudioMsg, Knob, Message, Orientation, Param, Range};
 
-use iced::{Align, Column, Container, Element, Length, Text};
+use iced::{
+    button, text_input, Align, Button, Column, Container, Element, Length, Text, TextInput,
+};
 
-use iced_audio::{knob, Knob};
+use iced_audio::{h_slider, knob, v_slider, Normal};
+
+use rand::Rng;
 
 use std::sync::mpsc;
 
+// the widget that controls the parameter
+enum Control {
+    Knob(knob::State<u32>),
+    HSlider(h_slider::State<u32>),
+    VSlider(v_slider::State<u32>),
+}
+
+impl Control {
+    fn param(&self) -> &iced_audio::Param<u32> {
+        match self {
+            Control::Knob(state) => &state.param,
+            Control::HSlider(state) => &state.param,
+            Control::VSlider(state) => &state.param,
+        }
+    }
+
+    fn param_mut(&mut self) -> &mut iced_audio::Param<u32> {
+        match self {
+            Control::Knob(state) => &mut state.param,
+            Control::HSlider(state) => &mut state.param,
+            Control::VSlider(state) => &mut state.param,
+        }
+    }
+
+    fn view(&mut self, style: &style::Theme, enabled: bool) -> Element<Message> {
+        match self {
+            Control::Knob(state) => iced_audio::Knob::new(state, Message::ParamMoved)
+                .size(Length::from(27))
+                .style(if enabled {
+                    style.knob()
+                } else {
+                    style.disabled_knob()
+                })
+                .into(),
+            Control::HSlider(state) => iced_audio::HSlider::new(state, Message::ParamMoved)
+                .width(Length::from(45))
+                .height(Length::from(14))
+                .style(if enabled {
+                    style.h_slider()
+                } else {
+                    style.disabled_h_slider()
+                })
+                .into(),
+            Control::VSlider(state) => iced_audio::VSlider::new(state, Message::ParamMoved)
+                .width(Length::from(14))
+                .height(Length::from(35))
+                .style(if enabled {
+                    style.v_slider()
+                } else {
+                    style.disabled_v_slider()
+                })
+                .into(),
+        }
+    }
+}
+
+/// A continuous parameter, controlled with a knob or a slider.
 pub struct KnobBlock {
     pub label: String,
     pub range: Range,
-    knob_state: knob::State<u32>,
+    control: Control,
     value_text: String,
     value: f32,
+    skip_randomize: bool,
+    menu: BlockMenu,
+    format: ValueFormat,
+    value_text_btn: button::State,
+    value_text_input: text_input::State,
+    edit_text: Option<String>,
+    // the typed text couldn't be parsed, so the field stays open
+    invalid_text: bool,
+    modulation: Option<Modulation>,
+    preferred_modulation: Option<Modulation>,
+    modulated_normal: Option<f32>,
 }
 
 impl KnobBlock {
-    pub fn new(id: u32, value: f32, default_value: f32, label: &str, range: Range) -> Self {
-        let param = blocks::create_param(id, value, default_value, &range);
+    pub fn new(id: u32, knob: Knob) -> Self {
+        let param = blocks::create_param(id, knob.value, knob.default_value, &knob.range);
+
+        Self::with_control(knob, Control::Knob(knob::State::new(param)))
+    }
+
+    /// Creates a block with the knob's settings that is controlled with a slider.
+    pub fn new_slider(id: u32, knob: Knob, orientation: Orientation) -> Self {
+        let param = blocks::create_param(id, knob.value, knob.default_value, &knob.range);
+
+        let control = match orientation {
+            Orientation::Horizontal => Control::HSlider(h_slider::State::new(param)),
+            Orientation::Vertical => Control::VSlider(v_slider::State::new(param)),
+        };
+
+        Self::with_control(knob, control)
+    }
 
+    fn with_control(knob: Knob, control: Control) -> Self {
         let mut new_knob = Self {
-            label: String::from(label),
-            range,
-            knob_state: knob::State::new(param),
+            label: String::from(knob.label),
+            range: knob.range,
+            control,
             value_text: String::new(),
             value: 0.0,
+            skip_randomize: knob.skip_randomize,
+            menu: BlockMenu::new(),
+            format: ValueFormat {
+                formatter: knob.formatter,
+                unit: knob.unit,
+                decimals: knob.decimals,
+            },
+            value_text_btn: button::State::new(),
+            value_text_input: text_input::State::new(),
+            edit_text: None,
+            invalid_text: false,
+            modulation: knob.modulation,
+            preferred_modulation: knob.modulation,
+            modulated_normal: None,
         };
 
         new_knob.update_text();
@@ -33,7 +137,7 @@ impl KnobBlock {
 
     pub fn add_params(&self, params: &mut Vec<Param>) {
         params.push(Param {
-            id: self.knob_state.param.id,
+            id: self.control.param().id,
             value: self.value,
         });
     }
@@ -41,67 +145,249 @@ impl KnobBlock {
     pub fn update(&mut self, message: Message, gui_to_audio_tx: &mpsc::Sender<GuiToAudioMsg>) {
         match message {
             Message::ParamMoved(id) => {
-                if self.knob_state.param.id == id {
+                if self.control.param().id == id {
                     self.update_text();
 
                     gui_to_audio_tx
                         .send(GuiToAudioMsg::ParamChanged(Param {
-                            id: self.knob_state.param.id,
+                            id: self.control.param().id,
                             value: self.value,
                         }))
                         .unwrap();
                 }
             }
+            Message::ModulationBtnPressed(id) => {
+                if self.control.param().id == id {
+                    self.modulation = Modulation::cycle(self.modulation, self.preferred_modulation);
+                    if self.modulation.is_none() {
+                        self.modulated_normal = None;
+                    }
+
+                    self.send_modulation(gui_to_audio_tx);
+                }
+            }
+            Message::ModulationCtrlPressed(id, ctrl) => {
+                if self.control.param().id == id {
+                    if let Some(modulation) = &mut self.modulation {
+                        modulation.adjust(ctrl);
+                        // cycling the source comes back to these settings
+                        self.preferred_modulation = self.modulation;
+
+                        self.send_modulation(gui_to_audio_tx);
+                    }
+                }
+            }
+            Message::ValueTextClicked(id) => {
+                if self.control.param().id == id {
+                    self.edit_text = Some(self.value_text.clone());
+                    self.value_text_input = text_input::State::focused();
+                } else {
+                    // only one value can be typed in at a time
+                    self.cancel_text_edit();
+                }
+            }
+            Message::ValueTextEdited(id, text) => {
+                if self.control.param().id == id {
+                    self.edit_text = Some(text);
+                    self.invalid_text = false;
+                }
+            }
+            Message::ValueTextSubmitted(id) => {
+                if self.control.param().id == id {
+                    if let Some(text) = self.edit_text.take() {
+                        match blocks::text_to_value(&text, &self.range, &self.format) {
+                            Some(value) => {
+                                self.control.param_mut().normal = self.range.to_normal(value);
+                                self.update_text();
+
+                                gui_to_audio_tx
+                                    .send(GuiToAudioMsg::ParamChanged(Param {
+                                        id: self.control.param().id,
+                                        value: self.value,
+                                    }))
+                                    .unwrap();
+                            }
+                            None => {
+                                eprintln!("`{}` is not a valid value for {}", text, self.label);
+                                self.edit_text = Some(text);
+                                self.invalid_text = true;
+                            }
+                        }
+                    }
+                }
+            }
             _ => {}
         }
     }
 
-    pub fn view(&mut self, style: &style::Theme) -> Element<Message> {
-        let knob = Knob::new(&mut self.knob_state, Message::ParamMoved)
-            .size(Length::from(27))
-            .style(style.knob());
+    pub fn view(
+        &mut self,
+        style: &style::Theme,
+        show_menu: bool,
+        enabled: bool,
+    ) -> Element<Message> {
+        if show_menu {
+            return self.menu.view(
+                self.control.param().id,
+                style,
+                Some(self.modulation.as_ref()),
+            );
+        }
+
+        let id = self.control.param().id;
+
+        let control = self.control.view(style, enabled);
+
+        let value_text: Element<Message> = match &self.edit_text {
+            _ if !enabled => Text::new(&self.value_text).size(12).into(),
+            Some(edit_text) => {
+                TextInput::new(&mut self.value_text_input, "", edit_text, move |text| {
+                    Message::ValueTextEdited(id, text)
+                })
+                .on_submit(Message::ValueTextSubmitted(id))
+                .size(12)
+                .padding(1)
+                .style(if self.invalid_text {
+                    style.invalid_text_input()
+                } else {
+                    style.text_input()
+                })
+                .into()
+            }
+            None => Button::new(
+                &mut self.value_text_btn,
+                Text::new(&self.value_text).size(12),
+            )
+            .padding(0)
+            .on_press(Message::ValueTextClicked(id))
+            .style(style.value_text_button())
+            .into(),
+        };
 
-        let column = Column::new()
+        let mut column = Column::new()
             .width(Length::from(blocks::BLOCK_WIDTH))
             .height(Length::from(blocks::BLOCK_HEIGHT))
             .align_items(Align::Center)
             .padding(10)
             .spacing(5)
-            .push(knob)
+            .push(control);
+
+        // shows the effective value of the parameter while it is modulated
+        if let Some(normal) = self.modulated_normal {
+            column = column.push(blocks::fill_bar(
+                normal,
+                40,
+                3,
+                style.mod_indicator(),
+                style.indicator_back(),
+            ));
+        }
+
+        column = column
             .push(Text::new(&self.label).size(12))
-            .push(Text::new(&self.value_text).size(12));
+            .push(value_text);
+
+        let container = Container::new(column).center_x().center_y();
 
-        Container::new(column)
-            .center_x()
-            .center_y()
-            .style(style.top_bar_container())
+        if enabled {
+            RightClickArea::new(
+                container.style(style.top_bar_container()),
+                Message::BlockRightClicked(id),
+            )
             .into()
+        } else {
+            DisabledArea::new(container.style(style.disabled_block_container())).into()
+        }
     }
 
     fn update_text(&mut self) {
-        let (text, value) = blocks::normal_to_text(&mut self.knob_state.param.normal, &self.range);
+        let (text, value) = blocks::normal_to_text(
+            &mut self.control.param_mut().normal,
+            &self.range,
+            &self.format,
+        );
 
         self.value_text = text;
         self.value = value;
     }
 
-    pub fn _id(&self) -> u32 {
-        self.knob_state.param.id
+    pub fn id(&self) -> u32 {
+        self.control.param().id
     }
 
-    pub fn _value(&self) -> f32 {
+    pub fn value(&self) -> f32 {
         self.value
     }
 
-    pub fn _set_value(&mut self, value: f32) {
+    pub fn set_value(&mut self, value: f32) {
         if self.value != value {
-            self.knob_state.param.normal = match self.range {
-                Range::Float(float_range) => float_range.to_normal(value),
-                Range::Int(int_range) => int_range.to_normal(value as i32),
-                Range::DB(db_range) => db_range.to_normal(value),
-                Range::Freq(freq_range) => freq_range.to_normal(value),
-            };
+            self.control.param_mut().normal = self.range.to_normal(value);
+
+            self.update_text();
+        }
+    }
+
+    /// Returns the minimum, maximum and default value.
+    pub fn value_range(&self) -> (f32, f32, f32) {
+        (
+            self.range.to_value(Normal::min()),
+            self.range.to_value(Normal::max()),
+            self.range.to_value(self.control.param().default_normal),
+        )
+    }
+
+    /// Parses typed text such as `-3 dB` into a value of this knob.
+    pub fn parse_value(&self, text: &str) -> Option<f32> {
+        blocks::text_to_value(text, &self.range, &self.format)
+    }
+
+    pub fn set_normal(&mut self, normal: Normal) {
+        self.control.param_mut().normal = normal;
+        self.update_text();
+    }
+
+    pub fn send_modulation(&self, gui_to_audio_tx: &mpsc::Sender<GuiToAudioMsg>) {
+        gui_to_audio_tx
+            .send(GuiToAudioMsg::SetModulation {
+                id: self.control.param().id,
+                modulation: self.modulation,
+                range: self.range,
+            })
+            .unwrap();
+    }
+
+    pub fn is_editing_text(&self) -> bool {
+        self.edit_text.is_some()
+    }
+
+    /// Closes the value text field and keeps the previous value.
+    pub fn cancel_text_edit(&mut self) {
+        self.edit_text = None;
+        self.invalid_text = false;
+    }
+
+    /// Cancels typing once a click outside of the field unfocused it.
+    pub fn cancel_unfocused_text_edit(&mut self) {
+        if !self.value_text_input.is_focused() {
+            self.cancel_text_edit();
+        }
+    }
+
+    pub fn update_modulation(&mut self, modulated_normals: &ModulatedNormals) {
+        if self.modulation.is_some() {
+            self.modulated_normal = Some(modulated_normals.get(self.control.param().id as usize));
+        }
+    }
+
+    pub fn reset(&mut self) {
+        let param = self.control.param_mut();
+        param.normal = param.default_normal;
+        self.update_text();
+    }
 
+    pub fn randomize<R: Rng>(&mut self, rng: &mut R) {
+        if !self.skip_randomize {
+            self.control.param_mut().normal = Normal::new(rng.gen::<f32>());
             self.update_text();
         }
     }
@@ -0,0 +1,205 @@
+use crate::meters::{ChannelLevel, MeterScale, MeterSource, Meters};
+use crate::{blocks, style, Message, Meter};
+
+use iced::{button, Align, Button, Column, Container, Element, Length, Row, Text};
+
+// the thickness of the peak hold marker
+static HOLD_MARKER_SIZE: u16 = 2;
+
+/// Shows the peak with hold, RMS level and clipping of both channels of the
+/// input or output.
+pub struct MeterBlock {
+    pub label: String,
+    source: MeterSource,
+    scale: MeterScale,
+    left: ChannelLevel,
+    right: ChannelLevel,
+    clip_btn: button::State,
+}
+
+impl MeterBlock {
+    pub fn new(meter: Meter) -> Self {
+        Self {
+            label: String::from(meter.label),
+            source: meter.source,
+            scale: meter.scale,
+            left: ChannelLevel::default(),
+            right: ChannelLevel::default(),
+            clip_btn: button::State::new(),
+        }
+    }
+
+    /// Picks up the latest levels.
+    pub fn update_meters(&mut self, meters: &Meters) {
+        let (left, right) = self.source.channels();
+        self.left = meters.channel(left);
+        self.right = meters.channel(right);
+    }
+
+    pub fn view(&mut self, style: &style::Theme, enabled: bool) -> Element<Message> {
+        let hold_db = self.left.hold_db.max(self.right.hold_db);
+        let hold_text = if hold_db > self.scale.floor_db() {
+            format!("{:.1} dB", hold_db)
+        } else {
+            String::from("-inf dB")
+        };
+
+        let bars = Row::new()
+            .height(Length::from(34))
+            .spacing(3)
+            .push(level_bar(style, &self.left, self.scale, true, 6))
+            .push(level_bar(style, &self.right, self.scale, true, 6));
+
+        let column = Column::new()
+            .width(Length::from(blocks::BLOCK_WIDTH))
+            .height(Length::from(blocks::BLOCK_HEIGHT))
+            .align_items(Align::Center)
+            .padding(6)
+            .spacing(4)
+            .push(clip_button(
+                style,
+                &mut self.clip_btn,
+                "clip",
+                self.left.clipped || self.right.clipped,
+                self.source,
+                enabled,
+            ))
+            .push(bars)
+            .push(Text::new(hold_text).size(11))
+            .push(Text::new(&self.label).size(12));
+
+        Container::new(column)
+            .center_x()
+            .center_y()
+            .style(if enabled {
+                style.top_bar_container()
+            } else {
+                style.disabled_block_container()
+            })
+            .into()
+    }
+}
+
+/// A small horizontal meter of both channels for the top bar. Its label lights
+/// up when a channel clipped.
+pub fn compact_meter<'a>(
+    style: &style::Theme,
+    clip_btn: &'a mut button::State,
+    label: &str,
+    meters: &Meters,
+    source: MeterSource,
+    scale: MeterScale,
+) -> Element<'a, Message> {
+    let (left, right) = source.channels();
+    let left = meters.channel(left);
+    let right = meters.channel(right);
+
+    let bars = Column::new()
+        .width(Length::from(70))
+        .spacing(2)
+        .push(level_bar(style, &left, scale, false, 4))
+        .push(level_bar(style, &right, scale, false, 4));
+
+    Row::new()
+        .align_items(Align::Center)
+        .spacing(4)
+        .push(clip_button(
+            style,
+            clip_btn,
+            label,
+            left.clipped || right.clipped,
+            source,
+            true,
+        ))
+        .push(bars)
+        .into()
+}
+
+// shows whether a channel clipped, and resets the latch when pressed
+fn clip_button<'a>(
+    style: &style::Theme,
+    state: &'a mut button::State,
+    label: &str,
+    clipped: bool,
+    source: MeterSource,
+    enabled: bool,
+) -> Element<'a, Message> {
+    let mut button = Button::new(state, Text::new(label).size(10))
+        .padding(1)
+        .style(if clipped {
+            style.clipped_button()
+        } else {
+            style.clip_button()
+        });
+
+    if enabled {
+        button = button.on_press(Message::ClipBtnPressed(source));
+    }
+
+    button.into()
+}
+
+// the RMS level, the peak above it and the peak hold marker, filling the bar
+// from the bottom or the left
+fn level_bar<'a>(
+    style: &style::Theme,
+    level: &ChannelLevel,
+    scale: MeterScale,
+    vertical: bool,
+    thickness: u16,
+) -> Element<'a, Message> {
+    let rms = scale.to_normal(level.rms_db);
+    let peak = scale.to_normal(level.peak_db).max(rms);
+    let hold = scale.to_normal(level.hold_db).max(peak);
+
+    let portion = |normal: f32| Length::FillPortion((normal * 1000.0) as u16);
+
+    let mut segments = vec![
+        (portion(rms), style.meter_rms()),
+        (portion(peak - rms), style.meter_peak()),
+        (portion(hold - peak), style.indicator_back()),
+    ];
+    if hold > 0.0 {
+        segments.push((Length::from(HOLD_MARKER_SIZE), style.meter_hold()));
+    }
+    segments.push((portion(1.0 - hold), style.indicator_back()));
+    // an empty segment would not shrink away, `FillPortion(0)` still takes space
+    segments.retain(|(length, _)| *length != Length::FillPortion(0));
+
+    if vertical {
+        segments
+            .into_iter()
+            .rev()
+            .fold(
+                Column::new()
+                    .width(Length::from(thickness))
+                    .height(Length::Fill),
+                |column, (length, segment_style)| {
+                    column.push(
+                        Container::new(Text::new(""))
+                            .width(Length::Fill)
+                            .height(length)
+                            .style(segment_style),
+                    )
+                },
+            )
+            .into()
+    } else {
+        segments
+            .into_iter()
+            .fold(
+                Row::new()
+                    .width(Length::Fill)
+                    .height(Length::from(thickness)),
+                |row, (length, segment_style)| {
+                    row.push(
+                        Container::new(Text::new(""))
+                            .width(length)
+                            .height(Length::Fill)
+                            .style(segment_style),
+                    )
+                },
+            )
+            .into()
+    }
+}
@@ -1,11 +1,49 @@
-use iced_audio::{Normal, Param};
+use iced::{
+    button, container, Align, Button, Column, Container, Element, HorizontalAlignment, Length, Row,
+    Text,
+};
+use iced_audio::Normal;
 
-use crate::Range;
+use crate::meters::Meters;
+use crate::modulation::{ModCtrl, ModulatedNormals, Modulation};
+use crate::output_values::OutputValues;
+use crate::scope::ScopeBuffer;
+use crate::{style, GuiToAudioMsg, Message, Param, Range};
 
+use rand::Rng;
+
+use std::sync::mpsc;
+
+mod disabled_area;
+mod double_click_area;
+mod frequency_response;
 mod knob;
+mod meter;
 mod option_knob;
+mod plot;
+mod readout;
+mod right_click_area;
+mod scope;
+mod spectrum;
+mod toggle;
+mod transfer_curve;
+mod trigger;
+mod xy_pad;
+pub use disabled_area::DisabledArea;
+pub use double_click_area::DoubleClickArea;
+pub use frequency_response::FrequencyResponseBlock;
 pub use knob::KnobBlock;
-pub use option_knob::OptionKnobBlock;
+pub use meter::{compact_meter, MeterBlock};
+pub use option_knob::{OptionDisplay, OptionKnobBlock};
+pub use plot::{Plot, Trace};
+pub use readout::ReadoutBlock;
+pub use right_click_area::RightClickArea;
+pub use scope::{ScopeBlock, ScopeCtrl};
+pub use spectrum::{SpectrumBlock, SpectrumCtrl};
+pub use toggle::ToggleBlock;
+pub use transfer_curve::TransferCurveBlock;
+pub use trigger::TriggerBlock;
+pub use xy_pad::XYPadBlock;
 
 static BLOCK_WIDTH: u16 = 65;
 static BLOCK_HEIGHT: u16 = 95;
@@ -13,61 +51,721 @@ static BLOCK_HEIGHT: u16 = 95;
 pub enum Block {
     Knob(KnobBlock),
     OptionKnob(OptionKnobBlock),
+    Readout(ReadoutBlock),
+    Toggle(ToggleBlock),
+    Trigger(TriggerBlock),
+    XYPad(XYPadBlock),
+    Meter(MeterBlock),
+    Scope(ScopeBlock),
+    Spectrum(SpectrumBlock),
+    TransferCurve(TransferCurveBlock),
+    FrequencyResponse(FrequencyResponseBlock),
+}
+
+impl Block {
+    /// Returns the ids of the block's parameters. Blocks that only display
+    /// values or send events have none.
+    pub fn ids(&self) -> Vec<u32> {
+        match self {
+            Block::Knob(block) => vec![block.id()],
+            Block::OptionKnob(block) => vec![block.id()],
+            Block::Toggle(block) => vec![block.id()],
+            Block::XYPad(block) => block.ids().to_vec(),
+            _ => Vec::new(),
+        }
+    }
+
+    /// Returns the label of the parameter if it belongs to this block.
+    pub fn param_label(&self, id: u32) -> Option<&str> {
+        match self {
+            Block::Knob(block) if block.id() == id => Some(&block.label),
+            Block::OptionKnob(block) if block.id() == id => Some(&block.label),
+            Block::Toggle(block) if block.id() == id => Some(&block.label),
+            Block::XYPad(block) => block.param_label(id),
+            _ => None,
+        }
+    }
+
+    pub fn add_params(&self, params: &mut Vec<Param>) {
+        match self {
+            Block::Knob(block) => block.add_params(params),
+            Block::OptionKnob(block) => block.add_params(params),
+            Block::Toggle(block) => block.add_params(params),
+            Block::XYPad(block) => block.add_params(params),
+            _ => {}
+        }
+    }
+
+    /// Returns the current value of the parameter if it belongs to this block.
+    pub fn param_value(&self, id: u32) -> Option<f32> {
+        match self {
+            Block::Knob(block) => {
+                if block.id() == id {
+                    return Some(block.value());
+                }
+            }
+            Block::OptionKnob(block) => {
+                if block.id() == id {
+                    return Some(block.value() as f32);
+                }
+            }
+            Block::Toggle(block) => {
+                if block.id() == id {
+                    return Some(block.value());
+                }
+            }
+            Block::XYPad(block) => return block.param_value(id),
+            _ => {}
+        }
+
+        None
+    }
+
+    /// Sets the value of the parameter if it belongs to this block. This does
+    /// not notify the audio thread.
+    pub fn set_param(&mut self, param: Param) {
+        match self {
+            Block::Knob(block) => {
+                if block.id() == param.id {
+                    block.set_value(param.value);
+                }
+            }
+            Block::OptionKnob(block) => {
+                if block.id() == param.id {
+                    block.set_value(param.value);
+                }
+            }
+            Block::Toggle(block) => {
+                if block.id() == param.id {
+                    block.set_value(param.value);
+                }
+            }
+            Block::XYPad(block) => block.set_param(param),
+            _ => {}
+        }
+    }
+
+    /// Returns the minimum, maximum and default value of the parameter if it
+    /// belongs to this block.
+    pub fn value_range(&self, id: u32) -> Option<(f32, f32, f32)> {
+        match self {
+            Block::Knob(block) if block.id() == id => Some(block.value_range()),
+            Block::OptionKnob(block) if block.id() == id => Some(block.value_range()),
+            Block::Toggle(block) if block.id() == id => Some(block.value_range()),
+            Block::XYPad(block) => block.value_range(id),
+            _ => None,
+        }
+    }
+
+    /// Parses typed text into a value of the parameter if it belongs to this
+    /// block.
+    pub fn parse_value(&self, id: u32, text: &str) -> Option<f32> {
+        match self {
+            Block::Knob(block) if block.id() == id => block.parse_value(text),
+            Block::OptionKnob(block) if block.id() == id => {
+                block.parse_value(text).map(|value| value as f32)
+            }
+            Block::Toggle(block) if block.id() == id => block.parse_value(text),
+            Block::XYPad(block) => block.parse_value(id, text),
+            _ => None,
+        }
+    }
+
+    /// Moves the parameter to the given normal if it belongs to this block, and
+    /// returns its new value. This does not notify the audio thread.
+    pub fn set_normal(&mut self, id: u32, normal: Normal) -> Option<Param> {
+        match self {
+            Block::Knob(block) => {
+                if block.id() == id {
+                    block.set_normal(normal);
+                    return Some(Param {
+                        id,
+                        value: block.value(),
+                    });
+                }
+            }
+            Block::OptionKnob(block) => {
+                if block.id() == id {
+                    block.set_normal(normal);
+                    return Some(Param {
+                        id,
+                        value: block.value() as f32,
+                    });
+                }
+            }
+            Block::Toggle(block) => {
+                if block.id() == id {
+                    block.set_normal(normal);
+                    return Some(Param {
+                        id,
+                        value: block.value(),
+                    });
+                }
+            }
+            Block::XYPad(block) => return block.set_normal(id, normal),
+            _ => {}
+        }
+
+        None
+    }
+
+    /// Sends the block's modulation routing to the audio thread.
+    pub fn send_modulation(&self, gui_to_audio_tx: &mpsc::Sender<GuiToAudioMsg>) {
+        if let Block::Knob(block) = self {
+            block.send_modulation(gui_to_audio_tx);
+        }
+    }
+
+    /// Returns `true` while a value is being typed into the block.
+    pub fn is_editing_text(&self) -> bool {
+        match self {
+            Block::Knob(block) => block.is_editing_text(),
+            _ => false,
+        }
+    }
+
+    pub fn cancel_text_edit(&mut self) {
+        if let Block::Knob(block) = self {
+            block.cancel_text_edit();
+        }
+    }
+
+    pub fn cancel_unfocused_text_edit(&mut self) {
+        if let Block::Knob(block) = self {
+            block.cancel_unfocused_text_edit();
+        }
+    }
+
+    pub fn update_modulation(&mut self, modulated_normals: &ModulatedNormals) {
+        if let Block::Knob(block) = self {
+            block.update_modulation(modulated_normals);
+        }
+    }
+
+    pub fn reset(&mut self) {
+        match self {
+            Block::Knob(block) => block.reset(),
+            Block::OptionKnob(block) => block.reset(),
+            Block::Toggle(block) => block.reset(),
+            Block::XYPad(block) => block.reset(),
+            _ => {}
+        }
+    }
+
+    pub fn randomize<R: Rng>(&mut self, rng: &mut R) {
+        match self {
+            Block::Knob(block) => block.randomize(rng),
+            Block::OptionKnob(block) => block.randomize(rng),
+            Block::Toggle(block) => block.randomize(rng),
+            Block::XYPad(block) => block.randomize(rng),
+            _ => {}
+        }
+    }
+
+    pub fn update(&mut self, message: Message, gui_to_audio_tx: &mpsc::Sender<GuiToAudioMsg>) {
+        match self {
+            Block::Knob(block) => block.update(message, gui_to_audio_tx),
+            Block::OptionKnob(block) => block.update(message, gui_to_audio_tx),
+            Block::Toggle(block) => block.update(message, gui_to_audio_tx),
+            Block::XYPad(block) => block.update(message, gui_to_audio_tx),
+            Block::Scope(block) => block.update(message),
+            Block::Spectrum(block) => block.update(message),
+            _ => {}
+        }
+    }
+
+    /// Picks up the latest values published by the DSP, the latest levels, the
+    /// latest signals and the current parameter values, indexed by id.
+    pub fn update_output(
+        &mut self,
+        out_values: &OutputValues,
+        meters: &Meters,
+        scope: &ScopeBuffer,
+        params: &[f32],
+    ) {
+        match self {
+            Block::Readout(block) => block.update_output(out_values),
+            Block::Meter(block) => block.update_meters(meters),
+            Block::Scope(block) => block.update_scope(scope),
+            Block::Spectrum(block) => block.update_spectrum(scope),
+            Block::TransferCurve(block) => block.update_curve(params, meters),
+            Block::FrequencyResponse(block) => block.update_response(params, scope.sample_rate()),
+            _ => {}
+        }
+    }
+
+    /// Disabled blocks are greyed out and can't be interacted with.
+    pub fn view(
+        &mut self,
+        style: &style::Theme,
+        show_menu: bool,
+        enabled: bool,
+    ) -> Element<Message> {
+        match self {
+            Block::Knob(block) => block.view(style, show_menu, enabled),
+            Block::OptionKnob(block) => block.view(style, show_menu, enabled),
+            Block::Readout(block) => block.view(style, enabled),
+            Block::Toggle(block) => block.view(style, show_menu, enabled),
+            Block::Trigger(block) => block.view(style, enabled),
+            Block::XYPad(block) => block.view(style, enabled),
+            Block::Meter(block) => block.view(style, enabled),
+            Block::Scope(block) => block.view(style, enabled),
+            Block::Spectrum(block) => block.view(style, enabled),
+            Block::TransferCurve(block) => block.view(style, enabled),
+            Block::FrequencyResponse(block) => block.view(style, enabled),
+        }
+    }
 }
 
-#[derive(Copy, Clone)]
-pub enum DecimalPlaces {
-    One,
-    Two,
+/// The menu shown in place of a block when it is right-clicked.
+pub struct BlockMenu {
+    learn_btn: button::State,
+    unlearn_btn: button::State,
+    modulation_btn: button::State,
+    slower_btn: button::State,
+    faster_btn: button::State,
+    less_depth_btn: button::State,
+    more_depth_btn: button::State,
+    clear_automation_btn: button::State,
+    close_btn: button::State,
 }
 
-fn float_text(value: f32, decimal_places: DecimalPlaces) -> String {
-    match decimal_places {
-        DecimalPlaces::One => format!("{:.1}", value),
-        DecimalPlaces::Two => format!("{:.2}", value),
+impl BlockMenu {
+    pub fn new() -> Self {
+        Self {
+            learn_btn: button::State::new(),
+            unlearn_btn: button::State::new(),
+            modulation_btn: button::State::new(),
+            slower_btn: button::State::new(),
+            faster_btn: button::State::new(),
+            less_depth_btn: button::State::new(),
+            more_depth_btn: button::State::new(),
+            clear_automation_btn: button::State::new(),
+            close_btn: button::State::new(),
+        }
+    }
+
+    /// `modulation` is the current modulation of blocks that can be modulated,
+    /// or `None` for blocks that can't. The menu is twice as wide while a
+    /// modulation is on, to make room for its rate and depth controls.
+    pub fn view(
+        &mut self,
+        id: u32,
+        style: &style::Theme,
+        modulation: Option<Option<&Modulation>>,
+    ) -> Element<Message> {
+        let mut column = Column::new()
+            .width(Length::from(BLOCK_WIDTH))
+            .height(Length::from(BLOCK_HEIGHT))
+            .align_items(Align::Center)
+            .padding(2)
+            .spacing(2)
+            .push(menu_button(
+                &mut self.learn_btn,
+                "MIDI learn",
+                Message::MidiLearnBtnPressed(id),
+                style,
+            ))
+            .push(menu_button(
+                &mut self.unlearn_btn,
+                "Unlearn",
+                Message::MidiUnlearnBtnPressed(id),
+                style,
+            ));
+
+        if let Some(modulation) = modulation {
+            let name = modulation.map_or("Off", |modulation| modulation.source.name());
+            column = column.push(menu_button(
+                &mut self.modulation_btn,
+                &format!("Mod: {}", name),
+                Message::ModulationBtnPressed(id),
+                style,
+            ));
+        }
+
+        column = column
+            .push(menu_button(
+                &mut self.clear_automation_btn,
+                "Clear auto",
+                Message::ClearAutomationBtnPressed(id),
+                style,
+            ))
+            .push(menu_button(
+                &mut self.close_btn,
+                "Close",
+                Message::CloseBlockMenu,
+                style,
+            ));
+
+        let content: Element<Message> = match modulation {
+            Some(Some(modulation)) => {
+                let settings = Column::new()
+                    .width(Length::from(BLOCK_WIDTH))
+                    .height(Length::from(BLOCK_HEIGHT))
+                    .align_items(Align::Center)
+                    .padding(2)
+                    .spacing(2)
+                    .push(Text::new("Rate").size(11))
+                    .push(Text::new(modulation.speed_text()).size(11))
+                    .push(
+                        Row::new()
+                            .width(Length::Fill)
+                            .spacing(2)
+                            .push(menu_button(
+                                &mut self.slower_btn,
+                                "-",
+                                Message::ModulationCtrlPressed(id, ModCtrl::Slower),
+                                style,
+                            ))
+                            .push(menu_button(
+                                &mut self.faster_btn,
+                                "+",
+                                Message::ModulationCtrlPressed(id, ModCtrl::Faster),
+                                style,
+                            )),
+                    )
+                    .push(Text::new("Depth").size(11))
+                    .push(Text::new(modulation.depth_text()).size(11))
+                    .push(
+                        Row::new()
+                            .width(Length::Fill)
+                            .spacing(2)
+                            .push(menu_button(
+                                &mut self.less_depth_btn,
+                                "-",
+                                Message::ModulationCtrlPressed(id, ModCtrl::LessDepth),
+                                style,
+                            ))
+                            .push(menu_button(
+                                &mut self.more_depth_btn,
+                                "+",
+                                Message::ModulationCtrlPressed(id, ModCtrl::MoreDepth),
+                                style,
+                            )),
+                    );
+
+                Row::new().push(column).push(settings).into()
+            }
+            _ => column.into(),
+        };
+
+        Container::new(content)
+            .center_x()
+            .center_y()
+            .style(style.top_bar_container())
+            .into()
     }
 }
 
-fn normal_to_text(normal: &mut Normal, range: &Range) -> (String, f32) {
-    match range {
-        Range::Float(float_range) => {
-            let value = float_range.to_value(*normal);
-            (float_text(value, DecimalPlaces::Two), value)
-        }
-        Range::Int(int_range) => {
-            int_range.snap_normal(normal);
-            let value = int_range.to_value(*normal);
-            (format!("{}", value), value as f32)
-        }
-        Range::DB(db_range) => {
-            let value = db_range.to_value(*normal);
-            (
-                format!("{} dB", float_text(value, DecimalPlaces::One)),
-                value,
-            )
-        }
-        Range::Freq(freq_range) => {
-            let value = freq_range.to_value(*normal);
-            if value < 1000.0 {
-                (
-                    format!("{} Hz", float_text(value, DecimalPlaces::One)),
-                    value,
+/// A bar filled from the left up to `normal`, e.g. to show a level. An empty
+/// side is left out, since `FillPortion(0)` would still take up space.
+pub fn fill_bar<'a>(
+    normal: f32,
+    width: u16,
+    height: u16,
+    fill_style: Box<dyn container::StyleSheet>,
+    back_style: Box<dyn container::StyleSheet>,
+) -> Element<'a, Message> {
+    let filled = (normal.max(0.0).min(1.0) * 1000.0) as u16;
+
+    let mut bar = Row::new()
+        .width(Length::from(width))
+        .height(Length::from(height));
+    if filled > 0 {
+        bar = bar.push(
+            Container::new(Text::new(""))
+                .width(Length::FillPortion(filled))
+                .height(Length::Fill)
+                .style(fill_style),
+        );
+    }
+    if filled < 1000 {
+        bar = bar.push(
+            Container::new(Text::new(""))
+                .width(Length::FillPortion(1000 - filled))
+                .height(Length::Fill)
+                .style(back_style),
+        );
+    }
+
+    bar.into()
+}
+
+fn menu_button<'a>(
+    state: &'a mut button::State,
+    label: &str,
+    on_press: Message,
+    style: &style::Theme,
+) -> Button<'a, Message> {
+    Button::new(
+        state,
+        Text::new(label)
+            .size(11)
+            .horizontal_alignment(HorizontalAlignment::Center),
+    )
+    .width(Length::Fill)
+    .padding(1)
+    .on_press(on_press)
+    .style(style.button())
+}
+
+/// How the value text of a block is displayed. Anything left as `None` uses
+/// the default format of the block's `Range`.
+pub struct ValueFormat {
+    pub formatter: Option<Box<dyn Fn(f32) -> String>>,
+    pub unit: Option<&'static str>,
+    pub decimals: Option<usize>,
+}
+
+impl Default for ValueFormat {
+    fn default() -> Self {
+        Self {
+            formatter: None,
+            unit: None,
+            decimals: None,
+        }
+    }
+}
+
+fn float_text(value: f32, decimals: usize) -> String {
+    format!("{:.*}", decimals, value)
+}
+
+fn with_unit(text: String, unit: Option<&str>) -> String {
+    match unit {
+        Some(unit) if !unit.is_empty() => format!("{} {}", text, unit),
+        _ => text,
+    }
+}
+
+fn normal_to_text(normal: &mut Normal, range: &Range, format: &ValueFormat) -> (String, f32) {
+    range.snap_normal(normal);
+
+    let value = range.to_value(*normal);
+
+    if let Some(formatter) = &format.formatter {
+        return (formatter(value), value);
+    }
+
+    let text = match range {
+        Range::Float(_) => with_unit(float_text(value, format.decimals.unwrap_or(2)), format.unit),
+        Range::Int(_) => {
+            let text = match format.decimals {
+                Some(decimals) => float_text(value, decimals),
+                None => format!("{}", value as i32),
+            };
+            with_unit(text, format.unit)
+        }
+        Range::DB(_) => with_unit(
+            float_text(value, format.decimals.unwrap_or(1)),
+            Some(format.unit.unwrap_or("dB")),
+        ),
+        Range::Freq(_) => {
+            if format.unit.is_some() {
+                with_unit(float_text(value, format.decimals.unwrap_or(1)), format.unit)
+            } else if value < 1000.0 {
+                format!("{} Hz", float_text(value, format.decimals.unwrap_or(1)))
+            } else {
+                format!(
+                    "{} kHz",
+                    float_text(value / 1000.0, format.decimals.unwrap_or(2))
                 )
+            }
+        }
+        Range::Skewed(_) => with_unit(float_text(value, format.decimals.unwrap_or(2)), format.unit),
+        Range::Bipolar(_) => with_unit(
+            format!("{:+.*}", format.decimals.unwrap_or(2), value),
+            format.unit,
+        ),
+        Range::Bool => String::from(if value >= 0.5 { "On" } else { "Off" }),
+        Range::Time(_) => {
+            if format.unit.is_some() {
+                with_unit(float_text(value, format.decimals.unwrap_or(1)), format.unit)
+            } else if value < 1000.0 {
+                format!("{} ms", float_text(value, format.decimals.unwrap_or(1)))
             } else {
-                (
-                    format!("{} kHz", float_text(value / 1000.0, DecimalPlaces::Two)),
-                    value,
+                format!(
+                    "{} s",
+                    float_text(value / 1000.0, format.decimals.unwrap_or(2))
                 )
             }
         }
+        Range::Percent(_) => with_unit(
+            float_text(value, format.decimals.unwrap_or(1)),
+            Some(format.unit.unwrap_or("%")),
+        ),
+        Range::Semitones(_) => with_unit(
+            format!("{:+}", value as i32),
+            Some(format.unit.unwrap_or("st")),
+        ),
+        Range::Cents(_) => with_unit(
+            format!("{:+.*}", format.decimals.unwrap_or(0), value),
+            Some(format.unit.unwrap_or("ct")),
+        ),
+    };
+
+    (text, value)
+}
+
+/// Parses a typed value such as `-3.5`, `-3.5 dB`, `440hz`, `1.2k` or `250ms`
+/// into a value of the given range. The result is not clamped.
+fn text_to_value(text: &str, range: &Range, format: &ValueFormat) -> Option<f32> {
+    let mut text = text.trim().to_lowercase();
+
+    if let Some(unit) = format.unit {
+        let unit = unit.to_lowercase();
+        if !unit.is_empty() && text.ends_with(&unit) {
+            text.truncate(text.len() - unit.len());
+        }
+    }
+
+    let mut multiplier = 1.0;
+    match range {
+        Range::DB(_) => {
+            if text.ends_with("db") {
+                text.truncate(text.len() - 2);
+            }
+        }
+        Range::Freq(_) => {
+            if text.ends_with("hz") {
+                text.truncate(text.len() - 2);
+            }
+            if text.trim_end().ends_with('k') {
+                text = String::from(text.trim_end());
+                text.pop();
+                multiplier = 1000.0;
+            }
+        }
+        Range::Bool => {
+            return match text.as_str() {
+                "on" | "true" | "yes" | "1" => Some(1.0),
+                "off" | "false" | "no" | "0" => Some(0.0),
+                _ => None,
+            };
+        }
+        Range::Time(_) => {
+            if text.ends_with("ms") {
+                text.truncate(text.len() - 2);
+            } else if text.ends_with('s') {
+                text.pop();
+                multiplier = 1000.0;
+            }
+        }
+        Range::Percent(_) => {
+            if text.ends_with('%') {
+                text.pop();
+            }
+        }
+        Range::Semitones(_) => {
+            if text.ends_with("st") {
+                text.truncate(text.len() - 2);
+            }
+        }
+        Range::Cents(_) => {
+            if text.ends_with("ct") {
+                text.truncate(text.len() - 2);
+            }
+        }
+        _ => {}
+    }
+
+    let value = text.trim().parse::<f32>().ok()?;
+
+    if value.is_finite() {
+        Some(value * multiplier)
+    } else {
+        None
     }
 }
 
-pub fn create_param(id: u32, value: f32, default_value: f32, range: &Range) -> Param<u32> {
+pub fn create_param(
+    id: u32,
+    value: f32,
+    default_value: f32,
+    range: &Range,
+) -> iced_audio::Param<u32> {
     match range {
         Range::Float(float_range) => float_range.create_param(id, value, default_value),
         Range::Int(int_range) => int_range.create_param(id, value as i32, default_value as i32),
         Range::DB(db_range) => db_range.create_param(id, value, default_value),
         Range::Freq(freq_range) => freq_range.create_param(id, value, default_value),
+        _ => iced_audio::Param {
+            id,
+            normal: range.to_normal(value),
+            default_normal: range.to_normal(default_value),
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ZeroDBPos;
+
+    fn parse(text: &str, range: Range) -> Option<f32> {
+        text_to_value(text, &range, &ValueFormat::default())
+    }
+
+    #[test]
+    fn text_with_units() {
+        assert_eq!(
+            parse("-3.5 dB", Range::db(-60.0, 12.0, ZeroDBPos::Center)),
+            Some(-3.5)
+        );
+        assert_eq!(parse("440Hz", Range::freq(20.0, 20_000.0)), Some(440.0));
+        assert_eq!(parse("1.5 kHz", Range::freq(20.0, 20_000.0)), Some(1500.0));
+        assert_eq!(parse("250ms", Range::time(1.0, 5000.0)), Some(250.0));
+        assert_eq!(parse("2 s", Range::time(1.0, 5000.0)), Some(2000.0));
+        assert_eq!(parse("50%", Range::percent(0.0, 100.0)), Some(50.0));
+        assert_eq!(parse("on", Range::boolean()), Some(1.0));
+        assert_eq!(parse("loud", Range::float(0.0, 1.0)), None);
+        assert_eq!(parse("inf", Range::float(0.0, 1.0)), None);
+    }
+
+    #[test]
+    fn custom_unit() {
+        let format = ValueFormat {
+            unit: Some("dB GR"),
+            ..ValueFormat::default()
+        };
+
+        assert_eq!(
+            text_to_value("6 dB GR", &Range::float(0.0, 24.0), &format),
+            Some(6.0)
+        );
+    }
+
+    #[test]
+    fn displayed_text_round_trip() {
+        let ranges = [
+            Range::float(-1.0, 1.0),
+            Range::int(0, 10),
+            Range::db(-60.0, 12.0, ZeroDBPos::Center),
+            Range::freq(20.0, 20_000.0),
+            Range::skewed(0.0, 100.0, 0.5),
+            Range::bipolar(2.0),
+            Range::boolean(),
+            Range::time(1.0, 5000.0),
+            Range::percent(0.0, 100.0),
+            Range::semitones(-12, 12),
+            Range::cents(-100.0, 100.0),
+        ];
+
+        for range in ranges.iter() {
+            for normal in [0.0, 0.3, 0.75, 1.0].iter() {
+                let (text, value) =
+                    normal_to_text(&mut Normal::new(*normal), range, &ValueFormat::default());
+                let parsed = text_to_value(&text, range, &ValueFormat::default()).unwrap();
+
+                // the text is rounded to the displayed decimals
+                assert!(
+                    (parsed - value).abs() <= 0.5 + value.abs() * 0.01,
+                    "`{}` was parsed as {} instead of {}",
+                    text,
+                    parsed,
+                    value
+                );
+            }
+        }
     }
 }
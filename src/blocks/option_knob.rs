@@ -1,35 +1,109 @@
-use crate::{blocks, style, GuiToAudioMsg, Message, Param};
+use crate::blocks::{BlockMenu, DisabledArea, RightClickArea};
+use crate::{blocks, style, GuiToAudioMsg, Message, OptionKnob, Param};
 
-use iced::{Align, Column, Container, Element, Length, Text};
+use iced::{pick_list, Align, Column, Container, Element, Length, PickList, Radio, Text};
 
-use iced_audio::{knob, IntRange, Knob};
+use iced_audio::{knob, IntRange, Knob, Normal};
 
+use rand::Rng;
+
+use std::fmt;
 use std::sync::mpsc;
 
+/// How the options of an `OptionKnob` are presented.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum OptionDisplay {
+    Knob,
+    PickList,
+    Radio,
+}
+
+// an entry of the pick list
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct OptionItem {
+    index: usize,
+    name: String,
+}
+
+impl fmt::Display for OptionItem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name)
+    }
+}
+
+// the widget that selects the option
+enum Control {
+    Knob(knob::State<u32>),
+    PickList(pick_list::State<OptionItem>, iced_audio::Param<u32>),
+    Radio(iced_audio::Param<u32>),
+}
+
+impl Control {
+    fn param(&self) -> &iced_audio::Param<u32> {
+        match self {
+            Control::Knob(state) => &state.param,
+            Control::PickList(_, param) => param,
+            Control::Radio(param) => param,
+        }
+    }
+
+    fn param_mut(&mut self) -> &mut iced_audio::Param<u32> {
+        match self {
+            Control::Knob(state) => &mut state.param,
+            Control::PickList(_, param) => param,
+            Control::Radio(param) => param,
+        }
+    }
+}
+
+/// A parameter that selects one of several named options, controlled with a
+/// knob, a pick list or a group of radio buttons.
 pub struct OptionKnobBlock {
     pub label: String,
     pub int_range: IntRange,
-    knob_state: knob::State<u32>,
+    control: Control,
     value_text: String,
     value: i32,
     options: Vec<String>,
+    items: Vec<OptionItem>,
+    skip_randomize: bool,
+    menu: BlockMenu,
 }
 
 impl OptionKnobBlock {
-    pub fn new(id: u32, value: i32, default_value: i32, label: &str, options: Vec<String>) -> Self {
-        let num_options = options.len() as i32;
+    pub fn new(id: u32, knob: OptionKnob, display: OptionDisplay) -> Self {
+        let num_options = knob.options.len() as i32;
 
         let int_range = IntRange::new(0, num_options - 1);
 
-        let param = int_range.create_param(id, value, default_value);
+        let param = int_range.create_param(id, knob.value as i32, knob.default_value as i32);
+
+        let control = match display {
+            OptionDisplay::Knob => Control::Knob(knob::State::new(param)),
+            OptionDisplay::PickList => Control::PickList(pick_list::State::default(), param),
+            OptionDisplay::Radio => Control::Radio(param),
+        };
+
+        let items = knob
+            .options
+            .iter()
+            .enumerate()
+            .map(|(index, name)| OptionItem {
+                index,
+                name: name.clone(),
+            })
+            .collect();
 
         let mut new_knob = Self {
-            label: String::from(label),
+            label: String::from(knob.label),
             int_range,
-            knob_state: knob::State::new(param),
+            control,
             value_text: String::new(),
-            value,
-            options,
+            value: (knob.value as i32).min(num_options - 1),
+            options: knob.options,
+            items,
+            skip_randomize: knob.skip_randomize,
+            menu: BlockMenu::new(),
         };
 
         new_knob.update_text();
@@ -39,7 +113,7 @@ impl OptionKnobBlock {
 
     pub fn add_params(&self, params: &mut Vec<Param>) {
         params.push(Param {
-            id: self.knob_state.param.id,
+            id: self.control.param().id,
             value: self.value as f32,
         });
     }
@@ -47,16 +121,28 @@ impl OptionKnobBlock {
     pub fn update(&mut self, message: Message, gui_to_audio_tx: &mpsc::Sender<GuiToAudioMsg>) {
         match message {
             Message::ParamMoved(id) => {
-                if self.knob_state.param.id == id {
+                if self.control.param().id == id {
                     self.int_range
-                        .snap_normal(&mut self.knob_state.param.normal);
-                    self.value = self.int_range.to_value(self.knob_state.param.normal);
+                        .snap_normal(&mut self.control.param_mut().normal);
+                    self.value = self.int_range.to_value(self.control.param().normal);
 
                     self.update_text();
 
                     gui_to_audio_tx
                         .send(GuiToAudioMsg::ParamChanged(Param {
-                            id: self.knob_state.param.id,
+                            id: self.control.param().id,
+                            value: self.value as f32,
+                        }))
+                        .unwrap();
+                }
+            }
+            Message::OptionSelected(id, index) => {
+                if self.control.param().id == id {
+                    self.set_value(index as f32);
+
+                    gui_to_audio_tx
+                        .send(GuiToAudioMsg::ParamChanged(Param {
+                            id: self.control.param().id,
                             value: self.value as f32,
                         }))
                         .unwrap();
@@ -66,46 +152,182 @@ impl OptionKnobBlock {
         }
     }
 
-    pub fn view(&mut self, style: &style::Theme) -> Element<Message> {
-        let knob = Knob::new(&mut self.knob_state, Message::ParamMoved)
-            .size(Length::from(27))
-            .style(style.knob());
-
-        let column = Column::new()
-            .width(Length::from(blocks::BLOCK_WIDTH))
-            .height(Length::from(blocks::BLOCK_HEIGHT))
-            .align_items(Align::Center)
-            .padding(10)
-            .spacing(5)
-            .push(knob)
-            .push(Text::new(&self.label).size(12))
-            .push(Text::new(&self.value_text).size(12));
-
-        Container::new(column)
-            .center_x()
-            .center_y()
-            .style(style.top_bar_container())
+    pub fn view(
+        &mut self,
+        style: &style::Theme,
+        show_menu: bool,
+        enabled: bool,
+    ) -> Element<Message> {
+        if show_menu {
+            return self.menu.view(self.control.param().id, style, None);
+        }
+
+        let id = self.control.param().id;
+
+        let column = match &mut self.control {
+            Control::Knob(state) => {
+                let knob = Knob::new(state, Message::ParamMoved)
+                    .size(Length::from(27))
+                    .style(if enabled {
+                        style.knob()
+                    } else {
+                        style.disabled_knob()
+                    });
+
+                Column::new()
+                    .width(Length::from(blocks::BLOCK_WIDTH))
+                    .height(Length::from(blocks::BLOCK_HEIGHT))
+                    .align_items(Align::Center)
+                    .padding(10)
+                    .spacing(5)
+                    .push(knob)
+                    .push(Text::new(&self.label).size(12))
+                    .push(Text::new(&self.value_text).size(12))
+            }
+            Control::PickList(state, _) => {
+                let pick_list = PickList::new(
+                    state,
+                    &self.items[..],
+                    self.items.get(self.value as usize).cloned(),
+                    move |item| Message::OptionSelected(id, item.index),
+                )
+                .width(Length::Fill)
+                .text_size(12)
+                .style(if enabled {
+                    style.pick_list()
+                } else {
+                    style.disabled_pick_list()
+                });
+
+                Column::new()
+                    .width(Length::from(blocks::BLOCK_WIDTH * 2))
+                    .height(Length::from(blocks::BLOCK_HEIGHT))
+                    .align_items(Align::Center)
+                    .padding(10)
+                    .spacing(12)
+                    .push(pick_list)
+                    .push(Text::new(&self.label).size(12))
+            }
+            Control::Radio(_) => {
+                let value = self.value as usize;
+
+                let radios = self.options.iter().enumerate().fold(
+                    Column::new().width(Length::Fill).spacing(3),
+                    |radios, (index, option)| {
+                        radios.push(
+                            Radio::new(index, option, Some(value), move |index| {
+                                Message::OptionSelected(id, index)
+                            })
+                            .size(10)
+                            .text_size(12)
+                            .style(if enabled {
+                                style.radio()
+                            } else {
+                                style.disabled_radio()
+                            }),
+                        )
+                    },
+                );
+
+                // grows with the number of options
+                let height = blocks::BLOCK_HEIGHT.max(45 + 15 * self.options.len() as u16);
+
+                Column::new()
+                    .width(Length::from(blocks::BLOCK_WIDTH * 2))
+                    .height(Length::from(height))
+                    .align_items(Align::Center)
+                    .padding(10)
+                    .spacing(8)
+                    .push(radios)
+                    .push(Text::new(&self.label).size(12))
+            }
+        };
+
+        let container = Container::new(column).center_x().center_y();
+
+        if enabled {
+            RightClickArea::new(
+                container.style(style.top_bar_container()),
+                Message::BlockRightClicked(id),
+            )
             .into()
+        } else {
+            DisabledArea::new(container.style(style.disabled_block_container())).into()
+        }
     }
 
     fn update_text(&mut self) {
         self.value_text = self.options[self.value as usize].clone();
     }
 
-    pub fn _id(&self) -> u32 {
-        self.knob_state.param.id
+    pub fn id(&self) -> u32 {
+        self.control.param().id
     }
 
-    pub fn _value(&self) -> i32 {
+    pub fn value(&self) -> i32 {
         self.value
     }
 
-    pub fn _set_value(&mut self, value: i32) {
+    /// Rounds the value to the nearest option. Values outside of the options
+    /// are clamped, and non-finite values are ignored.
+    pub fn set_value(&mut self, value: f32) {
+        if !value.is_finite() {
+            return;
+        }
+        let value = (value.round() as i32)
+            .max(0)
+            .min(self.options.len() as i32 - 1);
+
         if self.value != value {
             self.value = value;
-            self.knob_state.param.normal = self.int_range.to_normal(value);
+            self.control.param_mut().normal = self.int_range.to_normal(value);
 
             self.update_text();
         }
     }
+
+    /// Returns the minimum, maximum and default value.
+    pub fn value_range(&self) -> (f32, f32, f32) {
+        (
+            0.0,
+            (self.options.len() - 1) as f32,
+            self.int_range.to_value(self.control.param().default_normal) as f32,
+        )
+    }
+
+    /// Parses the name or the index of an option.
+    pub fn parse_value(&self, text: &str) -> Option<i32> {
+        let text = text.trim();
+
+        if let Some(index) = self
+            .options
+            .iter()
+            .position(|option| option.eq_ignore_ascii_case(text))
+        {
+            return Some(index as i32);
+        }
+
+        match text.parse::<usize>() {
+            Ok(index) if index < self.options.len() => Some(index as i32),
+            _ => None,
+        }
+    }
+
+    pub fn set_normal(&mut self, mut normal: Normal) {
+        self.int_range.snap_normal(&mut normal);
+        let value = self.int_range.to_value(normal);
+        self.set_value(value as f32);
+    }
+
+    pub fn reset(&mut self) {
+        let value = self.int_range.to_value(self.control.param().default_normal);
+        self.set_value(value as f32);
+    }
+
+    pub fn randomize<R: Rng>(&mut self, rng: &mut R) {
+        if !self.skip_randomize {
+            let value = rng.gen_range(0, self.options.len() as i32);
+            self.set_value(value as f32);
+        }
+    }
 }
@@ -0,0 +1,192 @@
+use crate::style::{self, PlotStyle};
+use crate::Message;
+
+use iced::canvas::{self, Cursor, Frame, Geometry, Path, Stroke};
+use iced::{button, Button, Point, Rectangle, Text};
+
+static DOT_RADIUS: f32 = 3.0;
+
+/// The extent of a log frequency axis, in Hz.
+pub static MIN_FREQ: f32 = 20.0;
+pub static MAX_FREQ: f32 = 20_000.0;
+
+// the frequencies of the vertical grid lines on a log frequency axis
+static GRID_FREQS: [f32; 9] = [
+    50.0, 100.0, 200.0, 500.0, 1_000.0, 2_000.0, 5_000.0, 10_000.0, 20_000.0,
+];
+
+/// The spacing of the horizontal grid lines on a dB axis.
+pub static GRID_DB: f32 = 12.0;
+
+/// A line drawn on a `Plot`.
+pub struct Trace {
+    /// Normalized positions, with `(0.0, 0.0)` at the bottom left and
+    /// `(1.0, 1.0)` at the top right. Points outside are clamped to the edges.
+    pub points: Vec<(f32, f32)>,
+    /// Draws the trace in the secondary color, e.g. for the input.
+    pub secondary: bool,
+}
+
+/// A canvas program that draws traces over a grid, shared by the scope and the
+/// other plotting blocks.
+pub struct Plot {
+    // set when the plot is viewed
+    style: Option<PlotStyle>,
+    // normalized positions of the grid lines
+    v_lines: Vec<f32>,
+    h_lines: Vec<f32>,
+    traces: Vec<Trace>,
+    // a marker drawn over the traces, in normalized coordinates
+    dot: Option<(f32, f32)>,
+}
+
+impl Plot {
+    pub fn new() -> Self {
+        Self {
+            style: None,
+            v_lines: Vec::new(),
+            h_lines: Vec::new(),
+            traces: Vec::new(),
+            dot: None,
+        }
+    }
+
+    /// Sets the normalized x positions of the vertical grid lines and the y
+    /// positions of the horizontal ones.
+    pub fn set_grid(&mut self, v_lines: Vec<f32>, h_lines: Vec<f32>) {
+        self.v_lines = v_lines;
+        self.h_lines = h_lines;
+    }
+
+    pub fn set_traces(&mut self, traces: Vec<Trace>) {
+        self.traces = traces;
+    }
+
+    pub fn set_dot(&mut self, dot: Option<(f32, f32)>) {
+        self.dot = dot;
+    }
+
+    pub fn set_style(&mut self, style: PlotStyle) {
+        self.style = Some(style);
+    }
+}
+
+impl canvas::Program<Message> for Plot {
+    fn draw(&self, bounds: Rectangle, _cursor: Cursor) -> Vec<Geometry> {
+        let style = match self.style {
+            Some(style) => style,
+            None => return Vec::new(),
+        };
+
+        let size = bounds.size();
+        let mut frame = Frame::new(size);
+
+        frame.fill_rectangle(Point::ORIGIN, size, style.background);
+
+        let to_point = |x: f32, y: f32| {
+            Point::new(
+                x.max(0.0).min(1.0) * size.width,
+                (1.0 - y.max(0.0).min(1.0)) * size.height,
+            )
+        };
+
+        let grid = Path::new(|builder| {
+            for x in self.v_lines.iter() {
+                builder.move_to(to_point(*x, 0.0));
+                builder.line_to(to_point(*x, 1.0));
+            }
+            for y in self.h_lines.iter() {
+                builder.move_to(to_point(0.0, *y));
+                builder.line_to(to_point(1.0, *y));
+            }
+        });
+        frame.stroke(
+            &grid,
+            Stroke {
+                color: style.grid,
+                width: 1.0,
+                ..Stroke::default()
+            },
+        );
+
+        for trace in self.traces.iter() {
+            if trace.points.len() < 2 {
+                continue;
+            }
+
+            let path = Path::new(|builder| {
+                let (x, y) = trace.points[0];
+                builder.move_to(to_point(x, y));
+                for (x, y) in trace.points[1..].iter() {
+                    builder.line_to(to_point(*x, *y));
+                }
+            });
+            frame.stroke(
+                &path,
+                Stroke {
+                    color: if trace.secondary {
+                        style.secondary
+                    } else {
+                        style.primary
+                    },
+                    width: 1.0,
+                    ..Stroke::default()
+                },
+            );
+        }
+
+        if let Some((x, y)) = self.dot {
+            frame.fill(&Path::circle(to_point(x, y), DOT_RADIUS), style.primary);
+        }
+
+        vec![frame.into_geometry()]
+    }
+}
+
+/// The position of a frequency on a log frequency axis.
+pub fn freq_to_normal(freq: f32) -> f32 {
+    (freq / MIN_FREQ).ln() / (MAX_FREQ / MIN_FREQ).ln()
+}
+
+/// The frequency at a position on a log frequency axis.
+pub fn normal_to_freq(normal: f32) -> f32 {
+    MIN_FREQ * (MAX_FREQ / MIN_FREQ).powf(normal)
+}
+
+/// The positions of the vertical grid lines of a log frequency axis.
+pub fn log_freq_grid() -> Vec<f32> {
+    GRID_FREQS
+        .iter()
+        .map(|freq| freq_to_normal(*freq))
+        .collect()
+}
+
+/// The positions of the horizontal grid lines of a dB axis, one every
+/// `GRID_DB` below `max_db`.
+pub fn db_grid(min_db: f32, max_db: f32) -> Vec<f32> {
+    (1..)
+        .map(|i| max_db - i as f32 * GRID_DB)
+        .take_while(|db| *db > min_db)
+        .map(|db| (db - min_db) / (max_db - min_db))
+        .collect()
+}
+
+/// A small button in the controls below a plot, which does nothing while the
+/// block is disabled.
+pub fn ctrl_button<'a>(
+    style: &style::Theme,
+    state: &'a mut button::State,
+    text: &str,
+    on_press: Message,
+    enabled: bool,
+) -> Button<'a, Message> {
+    let mut button = Button::new(state, Text::new(text).size(10))
+        .padding(1)
+        .style(style.value_text_button());
+
+    if enabled {
+        button = button.on_press(on_press);
+    }
+
+    button
+}
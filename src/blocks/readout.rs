@@ -0,0 +1,83 @@
+use crate::blocks::ValueFormat;
+use crate::output_values::OutputValues;
+use crate::{blocks, style, Message, Range, Readout};
+
+use iced::{Align, Column, Container, Element, Length, Text};
+
+pub struct ReadoutBlock {
+    pub label: String,
+    range: Range,
+    index: usize,
+    format: ValueFormat,
+    value: Option<f32>,
+    value_text: String,
+    normal: f32,
+}
+
+impl ReadoutBlock {
+    /// `index` is the index of the readout's name in `OutputValues`.
+    pub fn new(index: usize, readout: Readout) -> Self {
+        Self {
+            label: String::from(readout.label),
+            range: readout.range,
+            index,
+            format: ValueFormat {
+                formatter: readout.formatter,
+                unit: readout.unit,
+                decimals: readout.decimals,
+            },
+            value: None,
+            value_text: String::from("-"),
+            normal: 0.0,
+        }
+    }
+
+    /// Picks up the latest value published by the DSP.
+    pub fn update_output(&mut self, out_values: &OutputValues) {
+        let value = out_values.value(self.index);
+
+        if self.value != Some(value) {
+            self.value = Some(value);
+
+            let mut normal = self.range.to_normal(value);
+            let (text, _) = blocks::normal_to_text(&mut normal, &self.range, &self.format);
+
+            self.value_text = text;
+            self.normal = normal.value();
+        }
+    }
+
+    pub fn view(&mut self, style: &style::Theme, enabled: bool) -> Element<Message> {
+        let bar = blocks::fill_bar(
+            self.normal,
+            45,
+            6,
+            if enabled {
+                style.readout_bar()
+            } else {
+                style.indicator_back()
+            },
+            style.indicator_back(),
+        );
+
+        let column = Column::new()
+            .width(Length::from(blocks::BLOCK_WIDTH))
+            .height(Length::from(blocks::BLOCK_HEIGHT))
+            .align_items(Align::Center)
+            .padding(10)
+            .spacing(8)
+            .push(Text::new(&self.value_text).size(14))
+            .push(bar)
+            .push(Text::new(&self.label).size(12));
+
+        Container::new(column)
+            .center_x()
+            .center_y()
+            .style(if enabled {
+                style.top_bar_container()
+            } else {
+                style.disabled_block_container()
+            })
+            .into()
+    }
+}
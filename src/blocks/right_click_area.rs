@@ -0,0 +1,92 @@
+use iced_native::{
+    layout, mouse, Clipboard, Element, Event, Hasher, Layout, Length, Point, Widget,
+};
+
+/// Wraps an element and produces a message when it is right-clicked. All other
+/// events are passed through to the wrapped element.
+pub struct RightClickArea<'a, Message, Renderer> {
+    content: Element<'a, Message, Renderer>,
+    on_right_click: Message,
+}
+
+impl<'a, Message, Renderer> RightClickArea<'a, Message, Renderer> {
+    pub fn new<T>(content: T, on_right_click: Message) -> Self
+    where
+        T: Into<Element<'a, Message, Renderer>>,
+    {
+        Self {
+            content: content.into(),
+            on_right_click,
+        }
+    }
+}
+
+impl<'a, Message, Renderer> Widget<Message, Renderer> for RightClickArea<'a, Message, Renderer>
+where
+    Message: Clone,
+    Renderer: iced_native::Renderer,
+{
+    fn width(&self) -> Length {
+        self.content.width()
+    }
+
+    fn height(&self) -> Length {
+        self.content.height()
+    }
+
+    fn layout(&self, renderer: &Renderer, limits: &layout::Limits) -> layout::Node {
+        self.content.layout(renderer, limits)
+    }
+
+    fn draw(
+        &self,
+        renderer: &mut Renderer,
+        defaults: &Renderer::Defaults,
+        layout: Layout<'_>,
+        cursor_position: Point,
+    ) -> Renderer::Output {
+        self.content
+            .draw(renderer, defaults, layout, cursor_position)
+    }
+
+    fn hash_layout(&self, state: &mut Hasher) {
+        self.content.hash_layout(state);
+    }
+
+    fn on_event(
+        &mut self,
+        event: Event,
+        layout: Layout<'_>,
+        cursor_position: Point,
+        messages: &mut Vec<Message>,
+        renderer: &Renderer,
+        clipboard: Option<&dyn Clipboard>,
+    ) {
+        if let Event::Mouse(mouse::Event::ButtonPressed(mouse::Button::Right)) = event {
+            if layout.bounds().contains(cursor_position) {
+                messages.push(self.on_right_click.clone());
+                return;
+            }
+        }
+
+        self.content.on_event(
+            event,
+            layout,
+            cursor_position,
+            messages,
+            renderer,
+            clipboard,
+        );
+    }
+}
+
+impl<'a, Message, Renderer> From<RightClickArea<'a, Message, Renderer>>
+    for Element<'a, Message, Renderer>
+where
+    Message: 'a + Clone,
+    Renderer: 'a + iced_native::Renderer,
+{
+    fn from(area: RightClickArea<'a, Message, Renderer>) -> Element<'a, Message, Renderer> {
+        Element::new(area)
+    }
+}
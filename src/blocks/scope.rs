@@ -0,0 +1,221 @@
+use crate::blocks::plot::ctrl_button;
+use crate::blocks::{Plot, Trace};
+use crate::scope::ScopeBuffer;
+use crate::{blocks, style, Message, Scope};
+
+use iced::{Align, Canvas, Column, Container, Element, Length, Row, Space, Text};
+
+// the time per division, in milliseconds
+static TIME_DIVS_MS: [f32; 9] = [0.1, 0.2, 0.5, 1.0, 2.0, 5.0, 10.0, 20.0, 50.0];
+
+static GAINS: [f32; 7] = [0.25, 0.5, 1.0, 2.0, 4.0, 8.0, 16.0];
+
+// the number of horizontal and vertical divisions
+static NUM_X_DIVS: usize = 10;
+static NUM_Y_DIVS: usize = 8;
+
+// long windows are drawn with about this many points per trace
+static MAX_POINTS: usize = 512;
+
+/// A control of a scope.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ScopeCtrl {
+    TriggerMode,
+    TimeDivDown,
+    TimeDivUp,
+    GainDown,
+    GainUp,
+}
+
+/// Shows the input and output of the app over each other.
+pub struct ScopeBlock {
+    pub label: String,
+    // the index of this scope, used to address its controls
+    index: usize,
+    time_div: usize,
+    gain: usize,
+    free_running: bool,
+    plot: Plot,
+    input: Vec<f32>,
+    output: Vec<f32>,
+    trigger_btn: button::State,
+    time_div_down_btn: button::State,
+    time_div_up_btn: button::State,
+    gain_down_btn: button::State,
+    gain_up_btn: button::State,
+}
+
+impl ScopeBlock {
+    pub fn new(index: usize, scope: Scope) -> Self {
+        let mut plot = Plot::new();
+        plot.set_grid(
+            (1..NUM_X_DIVS)
+                .map(|i| i as f32 / NUM_X_DIVS as f32)
+                .collect(),
+            (1..NUM_Y_DIVS)
+                .map(|i| i as f32 / NUM_Y_DIVS as f32)
+                .collect(),
+        );
+
+        Self {
+            label: String::from(scope.label),
+            index,
+            time_div: nearest(&TIME_DIVS_MS, scope.time_per_div_ms),
+            gain: nearest(&GAINS, scope.gain),
+            free_running: scope.free_running,
+            plot,
+            input: Vec::new(),
+            output: Vec::new(),
+            trigger_btn: button::State::new(),
+            time_div_down_btn: button::State::new(),
+            time_div_up_btn: button::State::new(),
+            gain_down_btn: button::State::new(),
+            gain_up_btn: button::State::new(),
+        }
+    }
+
+    pub fn update(&mut self, message: Message) {
+        if let Message::ScopeCtrlPressed(index, ctrl) = message {
+            if self.index == index {
+                match ctrl {
+                    ScopeCtrl::TriggerMode => self.free_running = !self.free_running,
+                    ScopeCtrl::TimeDivDown => self.time_div = self.time_div.saturating_sub(1),
+                    ScopeCtrl::TimeDivUp => {
+                        self.time_div = (self.time_div + 1).min(TIME_DIVS_MS.len() - 1)
+                    }
+                    ScopeCtrl::GainDown => self.gain = self.gain.saturating_sub(1),
+                    ScopeCtrl::GainUp => self.gain = (self.gain + 1).min(GAINS.len() - 1),
+                }
+            }
+        }
+    }
+
+    /// Picks up the latest signals and finds the window to show.
+    pub fn update_scope(&mut self, buffer: &ScopeBuffer) {
+        let sample_rate = buffer.sample_rate();
+        if sample_rate == 0.0 {
+            return;
+        }
+
+        let window = ((TIME_DIVS_MS[self.time_div] * NUM_X_DIVS as f32 * sample_rate / 1000.0)
+            as usize)
+            .max(2);
+
+        // twice the window leaves room to search for the trigger
+        let read_len = if self.free_running {
+            window
+        } else {
+            window * 2
+        };
+        buffer.read(read_len, &mut self.input, &mut self.output);
+
+        let len = self.output.len();
+        let latest_start = len.saturating_sub(window);
+
+        // the latest rising zero-crossing of the output that still fills the window
+        let start = if self.free_running {
+            latest_start
+        } else {
+            (1..=latest_start)
+                .rev()
+                .find(|i| self.output[i - 1] < 0.0 && self.output[*i] >= 0.0)
+                .unwrap_or(latest_start)
+        };
+
+        let gain = GAINS[self.gain];
+        let step = (window / MAX_POINTS).max(1);
+        let to_points = |signal: &[f32]| {
+            signal[start..len.min(start + window)]
+                .iter()
+                .enumerate()
+                .step_by(step)
+                .map(|(i, sample)| (i as f32 / window as f32, 0.5 + 0.5 * gain * sample))
+                .collect()
+        };
+
+        self.plot.set_traces(vec![
+            Trace {
+                points: to_points(&self.input),
+                secondary: true,
+            },
+            Trace {
+                points: to_points(&self.output),
+                secondary: false,
+            },
+        ]);
+    }
+
+    pub fn view(&mut self, style: &style::Theme, enabled: bool) -> Element<Message> {
+        let index = self.index;
+        let ctrl_button = |state, text, ctrl| {
+            ctrl_button(
+                style,
+                state,
+                text,
+                Message::ScopeCtrlPressed(index, ctrl),
+                enabled,
+            )
+        };
+
+        let controls = Row::new()
+            .align_items(Align::Center)
+            .spacing(4)
+            .push(Text::new(&self.label).size(12))
+            .push(Space::with_width(Length::Fill))
+            .push(ctrl_button(
+                &mut self.trigger_btn,
+                if self.free_running { "free" } else { "trig" },
+                ScopeCtrl::TriggerMode,
+            ))
+            .push(ctrl_button(
+                &mut self.time_div_down_btn,
+                "<",
+                ScopeCtrl::TimeDivDown,
+            ))
+            .push(Text::new(format!("{} ms/div", TIME_DIVS_MS[self.time_div])).size(10))
+            .push(ctrl_button(
+                &mut self.time_div_up_btn,
+                ">",
+                ScopeCtrl::TimeDivUp,
+            ))
+            .push(ctrl_button(
+                &mut self.gain_down_btn,
+                "-",
+                ScopeCtrl::GainDown,
+            ))
+            .push(Text::new(format!("x{}", GAINS[self.gain])).size(10))
+            .push(ctrl_button(&mut self.gain_up_btn, "+", ScopeCtrl::GainUp));
+
+        self.plot.set_style(style.plot());
+        let canvas = Canvas::new(&mut self.plot)
+            .width(Length::Fill)
+            .height(Length::Fill);
+
+        let column = Column::new()
+            .width(Length::from(blocks::BLOCK_WIDTH * 4))
+            .height(Length::from(blocks::BLOCK_HEIGHT))
+            .padding(6)
+            .spacing(4)
+            .push(canvas)
+            .push(controls);
+
+        Container::new(column)
+            .style(if enabled {
+                style.top_bar_container()
+            } else {
+                style.disabled_block_container()
+            })
+            .into()
+    }
+}
+
+// the index of the step closest to the value
+fn nearest(steps: &[f32], value: f32) -> usize {
+    let mut nearest = 0;
+    for (index, step) in steps.iter().enumerate() {
+        if (step - value).abs() < (steps[nearest] - value).abs() {
+            nearest = index;
+        }
+    }
+    nearest
+}
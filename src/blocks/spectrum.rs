@@ -0,0 +1,215 @@
+use crate::blocks::plot::{
+    ctrl_button, db_grid, freq_to_normal, log_freq_grid, GRID_DB, MAX_FREQ, MIN_FREQ,
+};
+use crate::blocks::{Plot, Trace};
+use crate::scope::ScopeBuffer;
+use crate::spectrum::{Analyzer, FftWindow, SpectrumMode};
+use crate::{blocks, style, Message, Spectrum};
+
+use iced::{Align, Canvas, Column, Container, Element, Length, Row, Space, Text};
+
+static FFT_SIZES: [usize; 6] = [512, 1024, 2048, 4096, 8192, 16384];
+
+// the bins are reduced to at most this many points per trace
+static MAX_POINTS: usize = 512;
+
+/// A control of a spectrum analyzer.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum SpectrumCtrl {
+    SizeDown,
+    SizeUp,
+    Window,
+    Mode,
+}
+
+/// Shows the spectrums of the input and output of the app over each other.
+pub struct SpectrumBlock {
+    pub label: String,
+    // the index of this analyzer, used to address its controls
+    index: usize,
+    size: usize,
+    window: FftWindow,
+    mode: SpectrumMode,
+    min_db: f32,
+    max_db: f32,
+    input_analyzer: Analyzer,
+    output_analyzer: Analyzer,
+    plot: Plot,
+    input: Vec<f32>,
+    output: Vec<f32>,
+    size_down_btn: button::State,
+    size_up_btn: button::State,
+    window_btn: button::State,
+    mode_btn: button::State,
+}
+
+impl SpectrumBlock {
+    pub fn new(index: usize, spectrum: Spectrum) -> Self {
+        let size = FFT_SIZES
+            .iter()
+            .position(|size| *size >= spectrum.fft_size)
+            .unwrap_or(FFT_SIZES.len() - 1);
+
+        let min_db = spectrum.min_db.min(spectrum.max_db - GRID_DB);
+        let max_db = spectrum.max_db;
+
+        let mut plot = Plot::new();
+        plot.set_grid(log_freq_grid(), db_grid(min_db, max_db));
+
+        Self {
+            label: String::from(spectrum.label),
+            index,
+            size,
+            window: spectrum.window,
+            mode: spectrum.mode,
+            min_db,
+            max_db,
+            input_analyzer: Analyzer::new(FFT_SIZES[size], spectrum.window),
+            output_analyzer: Analyzer::new(FFT_SIZES[size], spectrum.window),
+            plot,
+            input: Vec::new(),
+            output: Vec::new(),
+            size_down_btn: button::State::new(),
+            size_up_btn: button::State::new(),
+            window_btn: button::State::new(),
+            mode_btn: button::State::new(),
+        }
+    }
+
+    pub fn update(&mut self, message: Message) {
+        if let Message::SpectrumCtrlPressed(index, ctrl) = message {
+            if self.index == index {
+                match ctrl {
+                    SpectrumCtrl::SizeDown => self.size = self.size.saturating_sub(1),
+                    SpectrumCtrl::SizeUp => self.size = (self.size + 1).min(FFT_SIZES.len() - 1),
+                    SpectrumCtrl::Window => self.window = self.window.next(),
+                    SpectrumCtrl::Mode => self.mode = self.mode.next(),
+                }
+
+                if self.input_analyzer.size() != FFT_SIZES[self.size]
+                    || ctrl == SpectrumCtrl::Window
+                {
+                    self.input_analyzer = Analyzer::new(FFT_SIZES[self.size], self.window);
+                    self.output_analyzer = Analyzer::new(FFT_SIZES[self.size], self.window);
+                } else {
+                    self.input_analyzer.clear();
+                    self.output_analyzer.clear();
+                }
+            }
+        }
+    }
+
+    /// Picks up the latest signals and analyzes them.
+    pub fn update_spectrum(&mut self, buffer: &ScopeBuffer) {
+        let sample_rate = buffer.sample_rate();
+        if sample_rate == 0.0 {
+            return;
+        }
+
+        buffer.read(FFT_SIZES[self.size], &mut self.input, &mut self.output);
+        self.input_analyzer.analyze(&self.input, self.mode);
+        self.output_analyzer.analyze(&self.output, self.mode);
+
+        let min_db = self.min_db;
+        let max_db = self.max_db;
+        let to_points = |analyzer: &Analyzer| {
+            // the loudest bin of each point, so that narrow peaks stay visible
+            let mut levels: Vec<Option<f32>> = vec![None; MAX_POINTS];
+            for bin in 1..analyzer.num_bins() {
+                let freq = bin as f32 * sample_rate / analyzer.size() as f32;
+                if freq < MIN_FREQ || freq > MAX_FREQ {
+                    continue;
+                }
+
+                let point = (freq_to_normal(freq) * (MAX_POINTS - 1) as f32).round() as usize;
+                let db = analyzer.bin_db(bin);
+                levels[point] = Some(levels[point].map_or(db, |level| level.max(db)));
+            }
+
+            levels
+                .iter()
+                .enumerate()
+                .filter_map(|(point, level)| {
+                    level.map(|db| {
+                        (
+                            point as f32 / (MAX_POINTS - 1) as f32,
+                            (db - min_db) / (max_db - min_db),
+                        )
+                    })
+                })
+                .collect()
+        };
+
+        self.plot.set_traces(vec![
+            Trace {
+                points: to_points(&self.input_analyzer),
+                secondary: true,
+            },
+            Trace {
+                points: to_points(&self.output_analyzer),
+                secondary: false,
+            },
+        ]);
+    }
+
+    pub fn view(&mut self, style: &style::Theme, enabled: bool) -> Element<Message> {
+        let index = self.index;
+        let ctrl_button = |state, text, ctrl| {
+            ctrl_button(
+                style,
+                state,
+                text,
+                Message::SpectrumCtrlPressed(index, ctrl),
+                enabled,
+            )
+        };
+
+        let controls = Row::new()
+            .align_items(Align::Center)
+            .spacing(4)
+            .push(Text::new(&self.label).size(12))
+            .push(Space::with_width(Length::Fill))
+            .push(ctrl_button(
+                &mut self.size_down_btn,
+                "<",
+                SpectrumCtrl::SizeDown,
+            ))
+            .push(Text::new(format!("{}", FFT_SIZES[self.size])).size(10))
+            .push(ctrl_button(
+                &mut self.size_up_btn,
+                ">",
+                SpectrumCtrl::SizeUp,
+            ))
+            .push(ctrl_button(
+                &mut self.window_btn,
+                self.window.name(),
+                SpectrumCtrl::Window,
+            ))
+            .push(ctrl_button(
+                &mut self.mode_btn,
+                self.mode.name(),
+                SpectrumCtrl::Mode,
+            ));
+
+        self.plot.set_style(style.plot());
+        let canvas = Canvas::new(&mut self.plot)
+            .width(Length::Fill)
+            .height(Length::Fill);
+
+        let column = Column::new()
+            .width(Length::from(blocks::BLOCK_WIDTH * 4))
+            .height(Length::from(blocks::BLOCK_HEIGHT))
+            .padding(6)
+            .spacing(4)
+            .push(canvas)
+            .push(controls);
+
+        Container::new(column)
+            .style(if enabled {
+                style.top_bar_container()
+            } else {
+                style.disabled_block_container()
+            })
+            .into()
+    }
+}
@@ -0,0 +1,152 @@
+use crate::blocks::{BlockMenu, DisabledArea, RightClickArea};
+use crate::{blocks, style, GuiToAudioMsg, Message, Param, Toggle};
+
+use iced::{button, Align, Button, Column, Container, Element, HorizontalAlignment, Length, Text};
+
+use iced_audio::Normal;
+
+use rand::Rng;
+
+use std::sync::mpsc;
+
+/// An on/off parameter with the values `0.0` and `1.0`.
+pub struct ToggleBlock {
+    pub label: String,
+    id: u32,
+    on: bool,
+    default_on: bool,
+    skip_randomize: bool,
+    btn_state: button::State,
+    menu: BlockMenu,
+}
+
+impl ToggleBlock {
+    pub fn new(id: u32, toggle: Toggle) -> Self {
+        Self {
+            label: String::from(toggle.label),
+            id,
+            on: toggle.value,
+            default_on: toggle.default_value,
+            skip_randomize: toggle.skip_randomize,
+            btn_state: button::State::new(),
+            menu: BlockMenu::new(),
+        }
+    }
+
+    pub fn add_params(&self, params: &mut Vec<Param>) {
+        params.push(Param {
+            id: self.id,
+            value: self.value(),
+        });
+    }
+
+    pub fn update(&mut self, message: Message, gui_to_audio_tx: &mpsc::Sender<GuiToAudioMsg>) {
+        match message {
+            Message::ParamMoved(id) => {
+                if self.id == id {
+                    self.on = !self.on;
+
+                    gui_to_audio_tx
+                        .send(GuiToAudioMsg::ParamChanged(Param {
+                            id: self.id,
+                            value: self.value(),
+                        }))
+                        .unwrap();
+                }
+            }
+            _ => {}
+        }
+    }
+
+    pub fn view(
+        &mut self,
+        style: &style::Theme,
+        show_menu: bool,
+        enabled: bool,
+    ) -> Element<Message> {
+        if show_menu {
+            return self.menu.view(self.id, style, None);
+        }
+
+        let button = Button::new(
+            &mut self.btn_state,
+            Text::new(if self.on { "On" } else { "Off" })
+                .size(14)
+                .horizontal_alignment(HorizontalAlignment::Center),
+        )
+        .width(Length::from(40))
+        .on_press(Message::ParamMoved(self.id))
+        .style(if !enabled {
+            style.disabled_button()
+        } else if self.on {
+            style.toggle_on()
+        } else {
+            style.toggle_off()
+        });
+
+        let column = Column::new()
+            .width(Length::from(blocks::BLOCK_WIDTH))
+            .height(Length::from(blocks::BLOCK_HEIGHT))
+            .align_items(Align::Center)
+            .padding(10)
+            .spacing(12)
+            .push(button)
+            .push(Text::new(&self.label).size(12));
+
+        let container = Container::new(column).center_x().center_y();
+
+        if enabled {
+            RightClickArea::new(
+                container.style(style.top_bar_container()),
+                Message::BlockRightClicked(self.id),
+            )
+            .into()
+        } else {
+            DisabledArea::new(container.style(style.disabled_block_container())).into()
+        }
+    }
+
+    pub fn id(&self) -> u32 {
+        self.id
+    }
+
+    pub fn value(&self) -> f32 {
+        if self.on {
+            1.0
+        } else {
+            0.0
+        }
+    }
+
+    pub fn set_value(&mut self, value: f32) {
+        self.on = value >= 0.5;
+    }
+
+    pub fn set_normal(&mut self, normal: Normal) {
+        self.set_value(normal.value());
+    }
+
+    /// Returns the minimum, maximum and default value.
+    pub fn value_range(&self) -> (f32, f32, f32) {
+        (0.0, 1.0, if self.default_on { 1.0 } else { 0.0 })
+    }
+
+    /// Parses `on`/`off`, `true`/`false`, `yes`/`no` or `1`/`0`.
+    pub fn parse_value(&self, text: &str) -> Option<f32> {
+        match text.trim().to_lowercase().as_str() {
+            "on" | "true" | "yes" | "1" => Some(1.0),
+            "off" | "false" | "no" | "0" => Some(0.0),
+            _ => None,
+        }
+    }
+
+    pub fn reset(&mut self) {
+        self.on = self.default_on;
+    }
+
+    pub fn randomize<R: Rng>(&mut self, rng: &mut R) {
+        if !self.skip_randomize {
+            self.on = rng.gen::<bool>();
+        }
+    }
+}
@@ -0,0 +1,102 @@
+use crate::blocks::{Plot, Trace};
+use crate::meters::{self, Meters};
+use crate::{blocks, style, Message, TransferCurve};
+
+use iced::{Align, Canvas, Column, Container, Element, Length, Text};
+
+// the number of points the curve is evaluated at
+static NUM_POINTS: usize = 256;
+
+/// Plots the output of a function of the input and the parameters.
+pub struct TransferCurveBlock {
+    pub label: String,
+    curve: Box<dyn Fn(f32, &[f32]) -> f32>,
+    range: f32,
+    show_level: bool,
+    // the parameter values the curve was last evaluated with
+    params: Option<Vec<f32>>,
+    plot: Plot,
+}
+
+impl TransferCurveBlock {
+    pub fn new(transfer_curve: TransferCurve) -> Self {
+        let mut plot = Plot::new();
+        plot.set_grid(vec![0.25, 0.5, 0.75], vec![0.25, 0.5, 0.75]);
+
+        Self {
+            label: String::from(transfer_curve.label),
+            curve: transfer_curve.curve,
+            range: transfer_curve.range.abs().max(std::f32::EPSILON),
+            show_level: transfer_curve.show_level,
+            params: None,
+            plot,
+        }
+    }
+
+    /// Evaluates the curve again if a parameter changed, and moves the level
+    /// dot to the latest input peak.
+    pub fn update_curve(&mut self, params: &[f32], meters: &Meters) {
+        let range = self.range;
+        let to_normal = |value: f32| (value + range) / (2.0 * range);
+
+        if self.params.as_deref() != Some(params) {
+            let points = (0..NUM_POINTS)
+                .map(|i| {
+                    let x = -range + 2.0 * range * i as f32 / (NUM_POINTS - 1) as f32;
+                    (to_normal(x), to_normal((self.curve)(x, params)))
+                })
+                .collect();
+
+            self.plot.set_traces(vec![
+                // the unity line, for reference
+                Trace {
+                    points: vec![(0.0, 0.0), (1.0, 1.0)],
+                    secondary: true,
+                },
+                Trace {
+                    points,
+                    secondary: false,
+                },
+            ]);
+
+            self.params = Some(params.to_vec());
+        }
+
+        if self.show_level {
+            let peak_db = meters
+                .channel(meters::IN_L)
+                .peak_db
+                .max(meters.channel(meters::IN_R).peak_db);
+            let peak = 10.0f32.powf(peak_db / 20.0).min(range);
+
+            self.plot.set_dot(Some((
+                to_normal(peak),
+                to_normal((self.curve)(peak, params)),
+            )));
+        }
+    }
+
+    pub fn view(&mut self, style: &style::Theme, enabled: bool) -> Element<Message> {
+        self.plot.set_style(style.plot());
+        let canvas = Canvas::new(&mut self.plot)
+            .width(Length::Fill)
+            .height(Length::Fill);
+
+        let column = Column::new()
+            .width(Length::from(blocks::BLOCK_WIDTH * 2))
+            .height(Length::from(blocks::BLOCK_HEIGHT))
+            .align_items(Align::Center)
+            .padding(6)
+            .spacing(4)
+            .push(canvas)
+            .push(Text::new(&self.label).size(12));
+
+        Container::new(column)
+            .style(if enabled {
+                style.top_bar_container()
+            } else {
+                style.disabled_block_container()
+            })
+            .into()
+    }
+}
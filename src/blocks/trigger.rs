@@ -0,0 +1,58 @@
+use crate::{blocks, style, Message, Trigger};
+
+use iced::{button, Align, Button, Column, Container, Element, HorizontalAlignment, Length, Text};
+
+/// A button that sends a one-shot event to the DSP instead of a stored value.
+pub struct TriggerBlock {
+    pub label: String,
+    index: usize,
+    btn_state: button::State,
+}
+
+impl TriggerBlock {
+    /// `index` is the index of the trigger's name in `GuiSetup::trigger_names`.
+    pub fn new(index: usize, trigger: Trigger) -> Self {
+        Self {
+            label: String::from(trigger.label),
+            index,
+            btn_state: button::State::new(),
+        }
+    }
+
+    pub fn view(&mut self, style: &style::Theme, enabled: bool) -> Element<Message> {
+        let mut button = Button::new(
+            &mut self.btn_state,
+            Text::new("Fire")
+                .size(14)
+                .horizontal_alignment(HorizontalAlignment::Center),
+        )
+        .width(Length::from(40));
+
+        if enabled {
+            button = button
+                .on_press(Message::TriggerBtnPressed(self.index))
+                .style(style.toggle_off());
+        } else {
+            button = button.style(style.disabled_button());
+        }
+
+        let column = Column::new()
+            .width(Length::from(blocks::BLOCK_WIDTH))
+            .height(Length::from(blocks::BLOCK_HEIGHT))
+            .align_items(Align::Center)
+            .padding(10)
+            .spacing(12)
+            .push(button)
+            .push(Text::new(&self.label).size(12));
+
+        Container::new(column)
+            .center_x()
+            .center_y()
+            .style(if enabled {
+                style.top_bar_container()
+            } else {
+                style.disabled_block_container()
+            })
+            .into()
+    }
+}
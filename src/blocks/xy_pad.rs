@@ -0,0 +1,287 @@
+use crate::blocks::{DisabledArea, DoubleClickArea, ValueFormat};
+use crate::{blocks, style, GuiToAudioMsg, Knob, Message, Param, Range, XYPad};
+
+use iced::{Align, Column, Container, Element, Length, Row, Space, Text};
+
+use iced_audio::{xy_pad, Normal};
+
+use rand::Rng;
+
+use std::sync::mpsc;
+use std::time::Instant;
+
+// one of the two parameters of a pad
+struct Axis {
+    label: String,
+    range: Range,
+    format: ValueFormat,
+    value: f32,
+    value_text: String,
+    skip_randomize: bool,
+}
+
+impl Axis {
+    fn new(knob: Knob) -> Self {
+        Self {
+            label: String::from(knob.label),
+            range: knob.range,
+            format: ValueFormat {
+                formatter: knob.formatter,
+                unit: knob.unit,
+                decimals: knob.decimals,
+            },
+            value: 0.0,
+            value_text: String::new(),
+            skip_randomize: knob.skip_randomize,
+        }
+    }
+
+    fn update_text(&mut self, param: &mut iced_audio::Param<u32>) {
+        let (text, value) = blocks::normal_to_text(&mut param.normal, &self.range, &self.format);
+
+        self.value_text = text;
+        self.value = value;
+    }
+
+    // the label above the value beside the pad, or next to it below the pad
+    fn view(&self, vertical: bool) -> Element<Message> {
+        let label = Text::new(&self.label).size(10);
+        let value = Text::new(&self.value_text).size(10);
+
+        if vertical {
+            Column::new().push(label).push(value).into()
+        } else {
+            Row::new().spacing(4).push(label).push(value).into()
+        }
+    }
+}
+
+// places a readout along the pad at the position of the handle, so it follows
+// the crosshair
+fn follow_handle(readout: Element<Message>, normal: f32, vertical: bool) -> Element<Message> {
+    let before = (normal.max(0.0).min(1.0) * 1000.0) as u16;
+    let after = 1000 - before;
+
+    // an empty side is left out, `FillPortion(0)` would not shrink it
+    if vertical {
+        // the top of the pad is the maximum
+        let mut column = Column::new().height(Length::Fill);
+        if after > 0 {
+            column = column.push(Space::with_height(Length::FillPortion(after)));
+        }
+        column = column.push(readout);
+        if before > 0 {
+            column = column.push(Space::with_height(Length::FillPortion(before)));
+        }
+        column.into()
+    } else {
+        let mut row = Row::new().width(Length::Fill);
+        if before > 0 {
+            row = row.push(Space::with_width(Length::FillPortion(before)));
+        }
+        row = row.push(readout);
+        if after > 0 {
+            row = row.push(Space::with_width(Length::FillPortion(after)));
+        }
+        row.into()
+    }
+}
+
+/// Two continuous parameters, controlled together by dragging a handle across
+/// a pad.
+pub struct XYPadBlock {
+    pub label: String,
+    x: Axis,
+    y: Axis,
+    pad_state: xy_pad::State<u32>,
+    last_click: Option<Instant>,
+}
+
+impl XYPadBlock {
+    pub fn new(x_id: u32, y_id: u32, pad: XYPad) -> Self {
+        let param_x = blocks::create_param(x_id, pad.x.value, pad.x.default_value, &pad.x.range);
+        let param_y = blocks::create_param(y_id, pad.y.value, pad.y.default_value, &pad.y.range);
+
+        let mut new_pad = Self {
+            label: String::from(pad.label),
+            x: Axis::new(pad.x),
+            y: Axis::new(pad.y),
+            pad_state: xy_pad::State::new(param_x, param_y),
+            last_click: None,
+        };
+
+        new_pad.update_text();
+
+        new_pad
+    }
+
+    // returns the axis of the parameter and the parameter itself
+    fn axis(&self, id: u32) -> Option<(&Axis, &iced_audio::Param<u32>)> {
+        if self.pad_state.param_x.id == id {
+            Some((&self.x, &self.pad_state.param_x))
+        } else if self.pad_state.param_y.id == id {
+            Some((&self.y, &self.pad_state.param_y))
+        } else {
+            None
+        }
+    }
+
+    fn axis_mut(&mut self, id: u32) -> Option<(&mut Axis, &mut iced_audio::Param<u32>)> {
+        if self.pad_state.param_x.id == id {
+            Some((&mut self.x, &mut self.pad_state.param_x))
+        } else if self.pad_state.param_y.id == id {
+            Some((&mut self.y, &mut self.pad_state.param_y))
+        } else {
+            None
+        }
+    }
+
+    fn update_text(&mut self) {
+        self.x.update_text(&mut self.pad_state.param_x);
+        self.y.update_text(&mut self.pad_state.param_y);
+    }
+
+    pub fn add_params(&self, params: &mut Vec<Param>) {
+        params.push(Param {
+            id: self.pad_state.param_x.id,
+            value: self.x.value,
+        });
+        params.push(Param {
+            id: self.pad_state.param_y.id,
+            value: self.y.value,
+        });
+    }
+
+    pub fn update(&mut self, message: Message, gui_to_audio_tx: &mpsc::Sender<GuiToAudioMsg>) {
+        match message {
+            Message::ParamMoved(id) => {
+                if let Some((axis, param)) = self.axis_mut(id) {
+                    axis.update_text(param);
+
+                    gui_to_audio_tx
+                        .send(GuiToAudioMsg::ParamChanged(Param {
+                            id,
+                            value: axis.value,
+                        }))
+                        .unwrap();
+                }
+            }
+            Message::XYPadDoubleClicked(id) => {
+                if self.pad_state.param_x.id == id {
+                    self.reset();
+                }
+            }
+            _ => {}
+        }
+    }
+
+    pub fn view(&mut self, style: &style::Theme, enabled: bool) -> Element<Message> {
+        let x_id = self.pad_state.param_x.id;
+        let x_normal = self.pad_state.param_x.normal.value();
+        let y_normal = self.pad_state.param_y.normal.value();
+
+        let pad = iced_audio::XYPad::new(&mut self.pad_state, Message::ParamMoved)
+            .size(Length::from(56))
+            .style(if enabled {
+                style.xy_pad()
+            } else {
+                style.disabled_xy_pad()
+            });
+
+        // only a double-click on the pad itself resets it
+        let pad: Element<Message> = if enabled {
+            DoubleClickArea::new(pad, &mut self.last_click, Message::XYPadDoubleClicked(x_id))
+                .into()
+        } else {
+            pad.into()
+        };
+
+        let y_readout = Container::new(follow_handle(self.y.view(true), y_normal, true))
+            .width(Length::Fill)
+            .height(Length::from(56));
+
+        let column = Column::new()
+            .width(Length::from(blocks::BLOCK_WIDTH * 2))
+            .height(Length::from(blocks::BLOCK_HEIGHT))
+            .align_items(Align::Center)
+            .padding(6)
+            .spacing(2)
+            .push(Row::new().spacing(4).push(pad).push(y_readout))
+            .push(follow_handle(self.x.view(false), x_normal, false))
+            .push(Text::new(&self.label).size(12));
+
+        let container = Container::new(column).center_x().center_y();
+
+        if enabled {
+            container.style(style.top_bar_container()).into()
+        } else {
+            DisabledArea::new(container.style(style.disabled_block_container())).into()
+        }
+    }
+
+    pub fn ids(&self) -> [u32; 2] {
+        [self.pad_state.param_x.id, self.pad_state.param_y.id]
+    }
+
+    /// Returns the label of the axis that controls the parameter.
+    pub fn param_label(&self, id: u32) -> Option<&str> {
+        self.axis(id).map(|(axis, _)| axis.label.as_str())
+    }
+
+    pub fn param_value(&self, id: u32) -> Option<f32> {
+        self.axis(id).map(|(axis, _)| axis.value)
+    }
+
+    pub fn set_param(&mut self, param: Param) {
+        if let Some((axis, pad_param)) = self.axis_mut(param.id) {
+            if axis.value != param.value {
+                pad_param.normal = axis.range.to_normal(param.value);
+                axis.update_text(pad_param);
+            }
+        }
+    }
+
+    /// Returns the minimum, maximum and default value of the parameter.
+    pub fn value_range(&self, id: u32) -> Option<(f32, f32, f32)> {
+        self.axis(id).map(|(axis, param)| {
+            (
+                axis.range.to_value(Normal::min()),
+                axis.range.to_value(Normal::max()),
+                axis.range.to_value(param.default_normal),
+            )
+        })
+    }
+
+    pub fn parse_value(&self, id: u32, text: &str) -> Option<f32> {
+        let (axis, _) = self.axis(id)?;
+
+        blocks::text_to_value(text, &axis.range, &axis.format)
+    }
+
+    pub fn set_normal(&mut self, id: u32, normal: Normal) -> Option<Param> {
+        let (axis, param) = self.axis_mut(id)?;
+        param.normal = normal;
+        axis.update_text(param);
+
+        Some(Param {
+            id,
+            value: axis.value,
+        })
+    }
+
+    pub fn reset(&mut self) {
+        self.pad_state.param_x.normal = self.pad_state.param_x.default_normal;
+        self.pad_state.param_y.normal = self.pad_state.param_y.default_normal;
+        self.update_text();
+    }
+
+    pub fn randomize<R: Rng>(&mut self, rng: &mut R) {
+        if !self.x.skip_randomize {
+            self.pad_state.param_x.normal = Normal::new(rng.gen::<f32>());
+        }
+        if !self.y.skip_randomize {
+            self.pad_state.param_y.normal = Normal::new(rng.gen::<f32>());
+        }
+        self.update_text();
+    }
+}
@@ -0,0 +1,38 @@
+/// A condition on the value of another parameter, used to only show a block in
+/// some modes. Every block has two of them:
+///
+/// * `visible_if` only shows the block while the condition is met.
+/// * `enabled_if` greys out the block unless the condition is met.
+///
+/// Parameters are referred to by their key, and the value of an `OptionKnob`
+/// is the index of its option.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Condition {
+    Equals(&'static str, f32),
+    NotEquals(&'static str, f32),
+    OneOf(&'static str, Vec<f32>),
+    Above(&'static str, f32),
+    Below(&'static str, f32),
+}
+
+impl Condition {
+    pub(crate) fn key(&self) -> &'static str {
+        match self {
+            Condition::Equals(key, _)
+            | Condition::NotEquals(key, _)
+            | Condition::OneOf(key, _)
+            | Condition::Above(key, _)
+            | Condition::Below(key, _) => *key,
+        }
+    }
+
+    pub(crate) fn is_met(&self, value: f32) -> bool {
+        match self {
+            Condition::Equals(_, v) => value == *v,
+            Condition::NotEquals(_, v) => value != *v,
+            Condition::OneOf(_, values) => values.contains(&value),
+            Condition::Above(_, v) => value > *v,
+            Condition::Below(_, v) => value < *v,
+        }
+    }
+}
@@ -0,0 +1,43 @@
+use std::path::PathBuf;
+
+/// Returns the path of a file that belongs to the app with the given title,
+/// e.g. `~/.config/dsp_box/waveshaper___dsp_box.midimap`.
+pub fn app_file_path(title: &str, extension: &str) -> PathBuf {
+    let mut path = match std::env::var_os("XDG_CONFIG_HOME") {
+        Some(dir) => PathBuf::from(dir),
+        None => match std::env::var_os("HOME") {
+            Some(home) => PathBuf::from(home).join(".config"),
+            None => PathBuf::from("."),
+        },
+    };
+    path.push("dsp_box");
+
+    let name: String = title
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() {
+                c.to_ascii_lowercase()
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    path.push(format!("{}.{}", name, extension));
+
+    path
+}
+
+/// Writes the file, creating its parent directory if needed.
+pub fn write_app_file(path: &PathBuf, contents: &str) -> std::io::Result<()> {
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+    std::fs::write(path, contents)
+}
+
+/// A path in the temp directory for a file written by a test, unique to this
+/// test run.
+#[cfg(test)]
+pub(crate) fn temp_path(name: &str) -> PathBuf {
+    std::env::temp_dir().join(format!("dsp_box_test_{}_{}", std::process::id(), name))
+}
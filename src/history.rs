@@ -0,0 +1,224 @@
+use crate::Param;
+
+use std::time::{Duration, Instant};
+
+// a controller has no mouse release, so its gesture ends once it was still this long
+static REMOTE_IDLE_TIME: Duration = Duration::from_millis(500);
+
+static MAX_ENTRIES: usize = 256;
+
+#[derive(Copy, Clone)]
+struct Edit {
+    id: u32,
+    old_value: f32,
+    new_value: f32,
+}
+
+struct Entry {
+    edits: Vec<Edit>,
+}
+
+/// Undo/redo stacks for parameter edits made in the GUI.
+pub struct History {
+    undo_stack: Vec<Entry>,
+    redo_stack: Vec<Entry>,
+    // the parameter whose moves are merged into the last entry until the gesture ends
+    open_gesture: Option<u32>,
+    last_remote_move: Option<Instant>,
+}
+
+impl History {
+    pub fn new() -> Self {
+        Self {
+            undo_stack: Vec::new(),
+            redo_stack: Vec::new(),
+            open_gesture: None,
+            last_remote_move: None,
+        }
+    }
+
+    /// Records a single parameter move. Moves of the same parameter are merged
+    /// into one entry until `end_gesture` is called, e.g. on mouse release.
+    pub fn record(&mut self, id: u32, old_value: f32, new_value: f32) {
+        if old_value == new_value {
+            return;
+        }
+
+        if self.open_gesture == Some(id) {
+            if let Some(entry) = self.undo_stack.last_mut() {
+                if entry.edits.len() == 1 && entry.edits[0].id == id {
+                    entry.edits[0].new_value = new_value;
+                    return;
+                }
+            }
+        }
+
+        self.push(Entry {
+            edits: vec![Edit {
+                id,
+                old_value,
+                new_value,
+            }],
+        });
+
+        self.open_gesture = Some(id);
+    }
+
+    /// Records a move from a MIDI or OSC controller. Its gesture ends once the
+    /// controller was still for a moment.
+    pub fn record_remote(&mut self, id: u32, old_value: f32, new_value: f32, now: Instant) {
+        if let Some(last_move) = self.last_remote_move {
+            if now.duration_since(last_move) >= REMOTE_IDLE_TIME {
+                self.end_gesture();
+            }
+        }
+        self.last_remote_move = Some(now);
+
+        self.record(id, old_value, new_value);
+    }
+
+    pub fn end_gesture(&mut self) {
+        self.open_gesture = None;
+    }
+
+    /// Records a change of several parameters at once as a single entry.
+    pub fn record_all(&mut self, old_params: &[Param], new_params: &[Param]) {
+        let edits: Vec<Edit> = old_params
+            .iter()
+            .zip(new_params.iter())
+            .filter(|(old, new)| old.id == new.id && old.value != new.value)
+            .map(|(old, new)| Edit {
+                id: old.id,
+                old_value: old.value,
+                new_value: new.value,
+            })
+            .collect();
+
+        if !edits.is_empty() {
+            self.push(Entry { edits });
+        }
+
+        self.open_gesture = None;
+    }
+
+    fn push(&mut self, entry: Entry) {
+        if self.undo_stack.len() >= MAX_ENTRIES {
+            self.undo_stack.remove(0);
+        }
+        self.undo_stack.push(entry);
+        self.redo_stack.clear();
+    }
+
+    /// Returns the values to restore, if there is anything to undo.
+    pub fn undo(&mut self) -> Option<Vec<Param>> {
+        self.open_gesture = None;
+
+        let entry = self.undo_stack.pop()?;
+        let params = entry
+            .edits
+            .iter()
+            .map(|edit| Param {
+                id: edit.id,
+                value: edit.old_value,
+            })
+            .collect();
+        self.redo_stack.push(entry);
+
+        Some(params)
+    }
+
+    /// Returns the values to restore, if there is anything to redo.
+    pub fn redo(&mut self) -> Option<Vec<Param>> {
+        self.open_gesture = None;
+
+        let entry = self.redo_stack.pop()?;
+        let params = entry
+            .edits
+            .iter()
+            .map(|edit| Param {
+                id: edit.id,
+                value: edit.new_value,
+            })
+            .collect();
+        self.undo_stack.push(entry);
+
+        Some(params)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn moves_in_one_gesture_are_one_entry() {
+        let mut history = History::new();
+        history.record(0, 0.0, 0.1);
+        history.record(0, 0.1, 0.2);
+        history.record(0, 0.2, 0.3);
+        history.end_gesture();
+
+        let params = history.undo().unwrap();
+        assert_eq!(params.len(), 1);
+        assert_eq!(params[0].value, 0.0);
+        assert!(history.undo().is_none());
+    }
+
+    #[test]
+    fn ended_gesture_or_other_param_starts_a_new_entry() {
+        let mut history = History::new();
+        history.record(0, 0.0, 0.5);
+        history.end_gesture();
+        history.record(0, 0.5, 1.0);
+        history.record(1, 0.0, 0.25);
+
+        assert_eq!(history.undo().unwrap()[0].id, 1);
+        assert_eq!(history.undo().unwrap()[0].value, 0.5);
+        assert_eq!(history.undo().unwrap()[0].value, 0.0);
+    }
+
+    #[test]
+    fn redo_is_cleared_by_a_new_edit() {
+        let mut history = History::new();
+        history.record(0, 0.0, 0.5);
+        history.undo().unwrap();
+
+        assert_eq!(history.redo().unwrap()[0].value, 0.5);
+
+        history.undo().unwrap();
+        history.record(0, 0.0, 0.75);
+        assert!(history.redo().is_none());
+    }
+
+    #[test]
+    fn remote_gesture_ends_when_idle() {
+        let mut history = History::new();
+        let start = Instant::now();
+        let moved = start + Duration::from_millis(100);
+        history.record_remote(0, 0.0, 0.1, start);
+        history.record_remote(0, 0.1, 0.2, moved);
+        history.record_remote(0, 0.2, 0.3, moved + REMOTE_IDLE_TIME);
+
+        assert_eq!(history.undo().unwrap()[0].value, 0.2);
+        assert_eq!(history.undo().unwrap()[0].value, 0.0);
+    }
+
+    #[test]
+    fn oldest_entries_are_dropped() {
+        let mut history = History::new();
+        for i in 0..MAX_ENTRIES + 10 {
+            history.record(0, i as f32, i as f32 + 1.0);
+            history.end_gesture();
+        }
+
+        let mut num_entries = 0;
+        let mut oldest_value = None;
+        while let Some(params) = history.undo() {
+            num_entries += 1;
+            oldest_value = Some(params[0].value);
+        }
+
+        assert_eq!(num_entries, MAX_ENTRIES);
+        assert_eq!(oldest_value, Some(10.0));
+    }
+}
@@ -1,12 +1,19 @@
 use std::sync::{mpsc, Arc, Mutex};
 
 use crate::audio_thread::{AudioThread, GuiToAudioMsg};
+use crate::shared_state::SharedState;
 use crate::{DSPBoxApp, GuiSetup};
 
 pub fn run(dsp_app: Box<dyn DSPBoxApp + std::marker::Send>, gui_setup: GuiSetup) {
     let (gui_to_audio_tx, gui_to_audio_rx) = mpsc::channel::<GuiToAudioMsg>();
+    let (shared, retired_rx) = SharedState::new(&gui_setup);
 
-    let audio_thread = Arc::new(Mutex::new(AudioThread::new(dsp_app, gui_to_audio_rx)));
+    let audio_thread = Arc::new(Mutex::new(AudioThread::new(
+        dsp_app,
+        gui_to_audio_rx,
+        gui_setup.trigger_names.clone(),
+        shared.clone(),
+    )));
 
     // Create client
     let (client, _status) =
@@ -26,6 +33,9 @@ pub fn run(dsp_app: Box<dyn DSPBoxApp + std::marker::Send>, gui_setup: GuiSetup)
     let mut out_r = client
         .register_port("dsp_box_out_r", jack::AudioOut::default())
         .unwrap();
+    let midi_in = client
+        .register_port("dsp_box_midi_in", jack::MidiIn::default())
+        .unwrap();
     let in_l_name = in_l.name().unwrap();
     let in_r_name = in_r.name().unwrap();
     let out_l_name = out_l.name().unwrap();
@@ -36,6 +46,10 @@ pub fn run(dsp_app: Box<dyn DSPBoxApp + std::marker::Send>, gui_setup: GuiSetup)
     let process_callback = move |_: &jack::Client, ps: &jack::ProcessScope| -> jack::Control {
         let mut audio_thread = audio_thread_arc.lock().unwrap();
 
+        for raw_midi in midi_in.iter(ps) {
+            audio_thread.midi_event(raw_midi.bytes);
+        }
+
         audio_thread.process_audio_stereo(
             in_l.as_slice(ps),
             in_r.as_slice(ps),
@@ -94,7 +108,7 @@ pub fn run(dsp_app: Box<dyn DSPBoxApp + std::marker::Send>, gui_setup: GuiSetup)
     }
 
     // run the gui thread until app is closed
-    crate::run_gui(gui_setup, gui_to_audio_tx);
+    crate::run_gui(gui_setup, gui_to_audio_tx, shared, retired_rx);
 
     // shut down jack client
     active_client.deactivate().unwrap();
@@ -1,29 +1,71 @@
 extern crate iced;
 extern crate iced_audio;
+extern crate iced_native;
 extern crate jack;
+extern crate rand;
+extern crate rosc;
 extern crate sndfile;
 
 mod audio_player;
 mod audio_thread;
+mod automation;
 mod blocks;
+mod condition;
+mod config;
+mod history;
 mod jack_client;
+mod message_ring;
+mod meters;
+mod midi;
+mod modulation;
+mod osc;
+mod output_values;
+mod preset;
+mod range;
+mod response;
+mod scope;
+mod session;
+mod shared_state;
+mod spectrum;
 mod style;
 
-use audio_thread::GuiToAudioMsg;
+use audio_thread::{AudioToGuiMsg, GuiToAudioMsg, Retired};
 pub use audio_thread::{AudioProcessInfo, Param};
-
-use blocks::{Block, KnobBlock, OptionKnobBlock};
-
-use iced_audio::{DBRange, FloatRange, FreqRange, IntRange};
+pub use condition::Condition;
+pub use meters::{MeterScale, MeterSource};
+pub use modulation::{LfoShape, ModCtrl, ModSource, Modulation};
+pub use output_values::OutputValues;
+pub use range::{Range, SkewRange, ZeroDBPos};
+pub use response::ResponseSource;
+pub use rustfft::num_complex::Complex;
+pub use spectrum::{FftWindow, SpectrumMode};
+
+use blocks::{
+    compact_meter, Block, FrequencyResponseBlock, KnobBlock, MeterBlock, OptionDisplay,
+    OptionKnobBlock, ReadoutBlock, ScopeBlock, ScopeCtrl, SpectrumBlock, SpectrumCtrl, ToggleBlock,
+    TransferCurveBlock, TriggerBlock, XYPadBlock,
+};
+use message_ring::MessageRing;
+use meters::{Levels, Meters};
+use modulation::ModulatedNormals;
+use scope::ScopeBuffer;
+use shared_state::SharedState;
 
 use iced::{
-    button, executor, Application, Button, Column, Command, Container, Element,
+    button, executor, Align, Application, Button, Column, Command, Container, Element,
     HorizontalAlignment, Length, Row, Settings, Space, Subscription, Text, VerticalAlignment,
     time,
 };
 
-use std::sync::mpsc;
-use std::time::Instant;
+use iced_native::{keyboard, mouse};
+
+use std::net::SocketAddr;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{mpsc, Arc};
+use std::time::{Duration, Instant};
+
+// how often the session is saved while it is being remembered
+static SESSION_SAVE_INTERVAL: Duration = Duration::from_secs(1);
 
 pub trait DSPBoxApp {
     fn host_reset(&mut self, sample_rate: f32);
@@ -34,179 +76,1450 @@ pub fn run(dsp_app: Box<dyn DSPBoxApp + std::marker::Send>, gui_setup: GuiSetup)
     jack_client::run(dsp_app, gui_setup);
 }
 
-fn run_gui(gui_setup: GuiSetup, gui_to_audio_tx: mpsc::Sender<GuiToAudioMsg>) {
+fn run_gui(
+    gui_setup: GuiSetup,
+    gui_to_audio_tx: mpsc::Sender<GuiToAudioMsg>,
+    shared: SharedState,
+    retired_rx: mpsc::Receiver<Retired>,
+) {
     DSPBoxGUI::run(Settings {
         antialiasing: true,
         flags: Flags {
             gui_setup,
             gui_to_audio_tx,
+            shared,
+            retired_rx,
         },
         ..Settings::default()
     });
 }
 
-pub enum Range {
-    Float(FloatRange),
-    Int(IntRange),
-    DB(DBRange),
-    Freq(FreqRange),
+pub struct Knob {
+    pub label: &'static str,
+    /// A stable name that identifies this knob in presets, MIDI mappings and
+    /// external control, so saved data survives adding or reordering knobs.
+    /// Defaults to the label.
+    pub key: Option<&'static str>,
+    pub value: f32,
+    pub default_value: f32,
+    pub range: Range,
+    /// Leave this knob untouched when the parameters are randomized.
+    pub skip_randomize: bool,
+    /// Overrides the value text entirely, e.g. `Some(Box::new(|v| format!("{:.0}x", v)))`.
+    pub formatter: Option<Box<dyn Fn(f32) -> String>>,
+    /// Overrides the unit suffix of the value text, e.g. `Some("ms")`.
+    pub unit: Option<&'static str>,
+    /// Overrides the number of decimal places of the value text.
+    pub decimals: Option<usize>,
+    /// A modulation source that is routed to this knob on startup. It can also
+    /// be changed by right-clicking the knob.
+    pub modulation: Option<Modulation>,
+    /// See `Condition`.
+    pub visible_if: Option<Condition>,
+    /// See `Condition`.
+    pub enabled_if: Option<Condition>,
+}
+
+impl Default for Knob {
+    fn default() -> Self {
+        Self {
+            label: "",
+            key: None,
+            value: 0.0,
+            default_value: 0.0,
+            range: Range::default(),
+            skip_randomize: false,
+            formatter: None,
+            unit: None,
+            decimals: None,
+            modulation: None,
+            visible_if: None,
+            enabled_if: None,
+        }
+    }
+}
+
+/// The direction a slider moves in, see `GuiSetup::push_slider`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Orientation {
+    Horizontal,
+    Vertical,
+}
+
+pub struct OptionKnob {
+    pub label: &'static str,
+    /// A stable name that identifies this knob in presets, MIDI mappings and
+    /// external control. Defaults to the label.
+    pub key: Option<&'static str>,
+    pub value: u32,
+    pub default_value: u32,
+    /// There must be at least one option.
+    pub options: Vec<String>,
+    /// Leave this knob untouched when the parameters are randomized.
+    pub skip_randomize: bool,
+    /// See `Condition`.
+    pub visible_if: Option<Condition>,
+    /// See `Condition`.
+    pub enabled_if: Option<Condition>,
+}
+
+impl Default for OptionKnob {
+    fn default() -> Self {
+        Self {
+            label: "",
+            key: None,
+            value: 0,
+            default_value: 0,
+            options: Vec::new(),
+            skip_randomize: false,
+            visible_if: None,
+            enabled_if: None,
+        }
+    }
+}
+
+/// Two parameters controlled together by dragging a handle across a pad, such
+/// as cutoff and resonance. Double-click the pad to reset both.
+pub struct XYPad {
+    pub label: &'static str,
+    /// The parameter on the horizontal axis. Its `modulation`, `visible_if`
+    /// and `enabled_if` are ignored.
+    pub x: Knob,
+    /// The parameter on the vertical axis. Its `modulation`, `visible_if` and
+    /// `enabled_if` are ignored.
+    pub y: Knob,
+    /// See `Condition`.
+    pub visible_if: Option<Condition>,
+    /// See `Condition`.
+    pub enabled_if: Option<Condition>,
+}
+
+impl Default for XYPad {
+    fn default() -> Self {
+        Self {
+            label: "",
+            x: Knob::default(),
+            y: Knob::default(),
+            visible_if: None,
+            enabled_if: None,
+        }
+    }
+}
+
+/// An on/off switch. The DSP sees its parameter as `0.0` (off) or `1.0` (on).
+pub struct Toggle {
+    pub label: &'static str,
+    /// A stable name that identifies this toggle in presets, MIDI mappings and
+    /// external control. Defaults to the label.
+    pub key: Option<&'static str>,
+    pub value: bool,
+    pub default_value: bool,
+    /// Leave this toggle untouched when the parameters are randomized.
+    pub skip_randomize: bool,
+    /// See `Condition`.
+    pub visible_if: Option<Condition>,
+    /// See `Condition`.
+    pub enabled_if: Option<Condition>,
+}
+
+impl Default for Toggle {
+    fn default() -> Self {
+        Self {
+            label: "",
+            key: None,
+            value: false,
+            default_value: false,
+            skip_randomize: false,
+            visible_if: None,
+            enabled_if: None,
+        }
+    }
+}
+
+/// A button for actions such as retriggering an envelope or clearing a delay
+/// line. Each press is seen once by the DSP through
+/// `AudioProcessInfo::triggered` with the trigger's label.
+pub struct Trigger {
+    pub label: &'static str,
+    /// See `Condition`.
+    pub visible_if: Option<Condition>,
+    /// See `Condition`.
+    pub enabled_if: Option<Condition>,
+}
+
+impl Default for Trigger {
+    fn default() -> Self {
+        Self {
+            label: "",
+            visible_if: None,
+            enabled_if: None,
+        }
+    }
+}
+
+/// Shows the peak with hold, the RMS level and clipping of both channels of the
+/// input or the output. Click `clip` to reset the clip indicator.
+pub struct Meter {
+    pub label: &'static str,
+    pub source: MeterSource,
+    pub scale: MeterScale,
+    /// See `Condition`.
+    pub visible_if: Option<Condition>,
+    /// See `Condition`.
+    pub enabled_if: Option<Condition>,
+}
+
+impl Default for Meter {
+    fn default() -> Self {
+        Self {
+            label: "",
+            source: MeterSource::Output,
+            scale: MeterScale::DB60,
+            visible_if: None,
+            enabled_if: None,
+        }
+    }
+}
+
+/// Shows the input and the output of the app over each other, in different
+/// colors. The time per division, gain and trigger mode can be changed in the
+/// block.
+pub struct Scope {
+    pub label: &'static str,
+    /// The initial time per division, in milliseconds. It snaps to the nearest
+    /// step between 0.1 ms and 50 ms.
+    pub time_per_div_ms: f32,
+    /// The initial gain of both signals. It snaps to the nearest step between
+    /// 0.25 and 16.
+    pub gain: f32,
+    /// Start without waiting for a rising zero-crossing of the output.
+    pub free_running: bool,
+    /// See `Condition`.
+    pub visible_if: Option<Condition>,
+    /// See `Condition`.
+    pub enabled_if: Option<Condition>,
+}
+
+impl Default for Scope {
+    fn default() -> Self {
+        Self {
+            label: "",
+            time_per_div_ms: 1.0,
+            gain: 1.0,
+            free_running: false,
+            visible_if: None,
+            enabled_if: None,
+        }
+    }
+}
+
+/// Shows the spectrums of the input and the output of the app over each other,
+/// in different colors, on a log frequency axis from 20 Hz to 20 kHz. The FFT
+/// size, window and mode can be changed in the block.
+pub struct Spectrum {
+    pub label: &'static str,
+    /// The initial FFT size. It is rounded up to a power of two between 512 and
+    /// 16384.
+    pub fft_size: usize,
+    pub window: FftWindow,
+    pub mode: SpectrumMode,
+    /// The level at the bottom of the plot, in dBFS.
+    pub min_db: f32,
+    /// The level at the top of the plot, in dBFS.
+    pub max_db: f32,
+    /// See `Condition`.
+    pub visible_if: Option<Condition>,
+    /// See `Condition`.
+    pub enabled_if: Option<Condition>,
+}
+
+impl Default for Spectrum {
+    fn default() -> Self {
+        Self {
+            label: "",
+            fft_size: 4096,
+            window: FftWindow::Hann,
+            mode: SpectrumMode::Average,
+            min_db: -96.0,
+            max_db: 0.0,
+            visible_if: None,
+            enabled_if: None,
+        }
+    }
+}
+
+/// Plots a static input to output curve, such as the curve of a waveshaper or
+/// a compressor, over `[-range, range]` on both axes.
+pub struct TransferCurve {
+    pub label: &'static str,
+    /// Maps an input to an output given the parameter values, indexed like
+    /// `AudioProcessInfo::in_params`, e.g.
+    /// `Box::new(|x, params| (x * params[0]).tanh())`. The curve is plotted
+    /// again whenever a parameter changes.
+    pub curve: Box<dyn Fn(f32, &[f32]) -> f32>,
+    pub range: f32,
+    /// Shows the peak of the input as a dot moving along the curve.
+    pub show_level: bool,
+    /// See `Condition`.
+    pub visible_if: Option<Condition>,
+    /// See `Condition`.
+    pub enabled_if: Option<Condition>,
+}
+
+impl Default for TransferCurve {
+    fn default() -> Self {
+        Self {
+            label: "",
+            curve: Box::new(|x, _| x),
+            range: 1.0,
+            show_level: false,
+            visible_if: None,
+            enabled_if: None,
+        }
+    }
+}
+
+/// Plots the magnitude in dB and optionally the phase of a frequency response,
+/// such as that of a filter, on a log frequency axis from 20 Hz to 20 kHz. It
+/// is updated whenever a parameter changes.
+///
+/// A `ResponseSource::Measured` response comes from a fresh instance of the
+/// app, so it only reflects the parameters and not any other state of the
+/// running app.
+pub struct FrequencyResponse {
+    pub label: &'static str,
+    pub source: ResponseSource,
+    /// Also plots the phase, from -180 degrees at the bottom to 180 degrees at
+    /// the top.
+    pub show_phase: bool,
+    /// The magnitude at the bottom of the plot, in dB.
+    pub min_db: f32,
+    /// The magnitude at the top of the plot, in dB.
+    pub max_db: f32,
+    /// See `Condition`.
+    pub visible_if: Option<Condition>,
+    /// See `Condition`.
+    pub enabled_if: Option<Condition>,
+}
+
+impl Default for FrequencyResponse {
+    fn default() -> Self {
+        Self {
+            label: "",
+            source: ResponseSource::Analytic(Box::new(|_, _| Complex::new(1.0, 0.0))),
+            show_phase: false,
+            min_db: -48.0,
+            max_db: 12.0,
+            visible_if: None,
+            enabled_if: None,
+        }
+    }
+}
+
+/// Displays a value the DSP publishes with `OutputValues::publish` under the
+/// readout's label, such as gain reduction or a detected pitch.
+pub struct Readout {
+    pub label: &'static str,
+    /// The extent of the readout's bar. Values outside of it are clamped.
+    pub range: Range,
+    /// Overrides the value text entirely, e.g. a pitch readout could show
+    /// `Some(Box::new(|st| format!("{:+.0} st", st)))`. Works like
+    /// `Knob::formatter`.
+    pub formatter: Option<Box<dyn Fn(f32) -> String>>,
+    /// Overrides the unit suffix of the value text, e.g. `Some("dB GR")`.
+    pub unit: Option<&'static str>,
+    /// Overrides the number of decimal places of the value text.
+    pub decimals: Option<usize>,
+    /// See `Condition`.
+    pub visible_if: Option<Condition>,
+    /// See `Condition`.
+    pub enabled_if: Option<Condition>,
+}
+
+impl Default for Readout {
+    fn default() -> Self {
+        Self {
+            label: "",
+            range: Range::default(),
+            formatter: None,
+            unit: None,
+            decimals: None,
+            visible_if: None,
+            enabled_if: None,
+        }
+    }
 }
 
-impl Range {
-    pub fn float(min: f32, max: f32) -> Self {
-        Range::Float(FloatRange::new(min, max))
+// the conditions of a block, indexed like `GuiSetup::blocks`
+struct BlockConditions {
+    visible_if: Option<Condition>,
+    enabled_if: Option<Condition>,
+}
+
+struct Flags {
+    pub gui_setup: GuiSetup,
+    pub gui_to_audio_tx: mpsc::Sender<GuiToAudioMsg>,
+    pub shared: SharedState,
+    pub retired_rx: mpsc::Receiver<Retired>,
+}
+
+impl Default for Flags {
+    fn default() -> Self {
+        let (tx, _) = mpsc::channel();
+        let gui_setup = GuiSetup::default();
+        let (shared, retired_rx) = SharedState::new(&gui_setup);
+
+        Self {
+            gui_setup,
+            gui_to_audio_tx: tx,
+            shared,
+            retired_rx,
+        }
+    }
+}
+
+pub struct GuiSetup {
+    title: String,
+    blocks: Vec<Block>,
+    block_conditions: Vec<BlockConditions>,
+    audio_file_path: Option<String>,
+    audio_file_gain: f32,
+    next_id: u32,
+    bypassed: bool,
+    autoplay: bool,
+    osc_addr: Option<SocketAddr>,
+    remember_session: bool,
+    has_arg_overrides: bool,
+    // the session as set up in code, taken before the arguments override it
+    code_defaults: Option<session::Session>,
+    // the key of each parameter, indexed by id
+    keys: Vec<&'static str>,
+    output_names: Vec<&'static str>,
+    trigger_names: Vec<&'static str>,
+    top_bar_meters: Option<MeterScale>,
+    num_scopes: usize,
+    num_spectrums: usize,
+}
+
+impl GuiSetup {
+    pub fn new(title: &str) -> Self {
+        Self {
+            title: String::from(title),
+            blocks: Vec::new(),
+            block_conditions: Vec::new(),
+            audio_file_path: None,
+            audio_file_gain: 1.0,
+            next_id: 0,
+            bypassed: false,
+            autoplay: false,
+            osc_addr: None,
+            remember_session: false,
+            has_arg_overrides: false,
+            code_defaults: None,
+            keys: Vec::new(),
+            output_names: Vec::new(),
+            trigger_names: Vec::new(),
+            top_bar_meters: None,
+            num_scopes: 0,
+            num_spectrums: 0,
+        }
+    }
+
+    // every block is pushed here, so its conditions stay indexed like it
+    fn push_block(
+        &mut self,
+        block: Block,
+        visible_if: Option<Condition>,
+        enabled_if: Option<Condition>,
+    ) {
+        self.blocks.push(block);
+        self.block_conditions.push(BlockConditions {
+            visible_if,
+            enabled_if,
+        });
+    }
+
+    pub fn push_knob(&mut self, knob: Knob) {
+        self.push_knob_block(knob, None);
+    }
+
+    /// Pushes a fader with the settings of `knob` instead of a knob.
+    pub fn push_slider(&mut self, knob: Knob, orientation: Orientation) {
+        self.push_knob_block(knob, Some(orientation));
+    }
+
+    fn push_knob_block(&mut self, mut knob: Knob, orientation: Option<Orientation>) {
+        self.add_key(knob.key.unwrap_or(knob.label));
+        let visible_if = knob.visible_if.take();
+        let enabled_if = knob.enabled_if.take();
+
+        let block = match orientation {
+            Some(orientation) => KnobBlock::new_slider(self.next_id, knob, orientation),
+            None => KnobBlock::new(self.next_id, knob),
+        };
+        self.push_block(Block::Knob(block), visible_if, enabled_if);
+
+        self.next_id += 1;
+    }
+
+    pub fn push_option_knob(&mut self, knob: OptionKnob) {
+        self.push_option_block(knob, OptionDisplay::Knob);
+    }
+
+    fn push_option_block(&mut self, mut knob: OptionKnob, display: OptionDisplay) {
+        if knob.options.is_empty() {
+            panic!("Parameter {} has no options", knob.label);
+        }
+        self.add_key(knob.key.unwrap_or(knob.label));
+        let visible_if = knob.visible_if.take();
+        let enabled_if = knob.enabled_if.take();
+
+        self.push_block(
+            Block::OptionKnob(OptionKnobBlock::new(self.next_id, knob, display)),
+            visible_if,
+            enabled_if,
+        );
+
+        self.next_id += 1;
+    }
+
+    /// Pushes the options as a dropdown list. This produces the same value as
+    /// `push_option_knob`, so the DSP doesn't change.
+    pub fn push_pick_list(&mut self, knob: OptionKnob) {
+        self.push_option_block(knob, OptionDisplay::PickList);
+    }
+
+    /// Pushes the options as a vertical group of radio buttons. This produces
+    /// the same value as `push_option_knob`, so the DSP doesn't change.
+    pub fn push_radio_group(&mut self, knob: OptionKnob) {
+        self.push_option_block(knob, OptionDisplay::Radio);
+    }
+
+    pub fn push_xy_pad(&mut self, mut pad: XYPad) {
+        self.add_key(pad.x.key.unwrap_or(pad.x.label));
+        self.add_key(pad.y.key.unwrap_or(pad.y.label));
+        let visible_if = pad.visible_if.take();
+        let enabled_if = pad.enabled_if.take();
+
+        self.push_block(
+            Block::XYPad(XYPadBlock::new(self.next_id, self.next_id + 1, pad)),
+            visible_if,
+            enabled_if,
+        );
+
+        self.next_id += 2;
+    }
+
+    pub fn push_toggle(&mut self, mut toggle: Toggle) {
+        self.add_key(toggle.key.unwrap_or(toggle.label));
+        let visible_if = toggle.visible_if.take();
+        let enabled_if = toggle.enabled_if.take();
+
+        self.push_block(
+            Block::Toggle(ToggleBlock::new(self.next_id, toggle)),
+            visible_if,
+            enabled_if,
+        );
+
+        self.next_id += 1;
+    }
+
+    pub fn push_trigger(&mut self, mut trigger: Trigger) {
+        let visible_if = trigger.visible_if.take();
+        let enabled_if = trigger.enabled_if.take();
+
+        let index = match self.trigger_names.iter().position(|n| *n == trigger.label) {
+            Some(index) => index,
+            None => {
+                self.trigger_names.push(trigger.label);
+                self.trigger_names.len() - 1
+            }
+        };
+
+        self.push_block(
+            Block::Trigger(TriggerBlock::new(index, trigger)),
+            visible_if,
+            enabled_if,
+        );
+    }
+
+    pub fn push_meter(&mut self, mut meter: Meter) {
+        let visible_if = meter.visible_if.take();
+        let enabled_if = meter.enabled_if.take();
+
+        self.push_block(Block::Meter(MeterBlock::new(meter)), visible_if, enabled_if);
+    }
+
+    pub fn push_scope(&mut self, mut scope: Scope) {
+        let visible_if = scope.visible_if.take();
+        let enabled_if = scope.enabled_if.take();
+
+        self.push_block(
+            Block::Scope(ScopeBlock::new(self.num_scopes, scope)),
+            visible_if,
+            enabled_if,
+        );
+        self.num_scopes += 1;
+    }
+
+    pub fn push_spectrum(&mut self, mut spectrum: Spectrum) {
+        let visible_if = spectrum.visible_if.take();
+        let enabled_if = spectrum.enabled_if.take();
+
+        self.push_block(
+            Block::Spectrum(SpectrumBlock::new(self.num_spectrums, spectrum)),
+            visible_if,
+            enabled_if,
+        );
+        self.num_spectrums += 1;
+    }
+
+    pub fn push_transfer_curve(&mut self, mut transfer_curve: TransferCurve) {
+        let visible_if = transfer_curve.visible_if.take();
+        let enabled_if = transfer_curve.enabled_if.take();
+
+        self.push_block(
+            Block::TransferCurve(TransferCurveBlock::new(transfer_curve)),
+            visible_if,
+            enabled_if,
+        );
+    }
+
+    pub fn push_frequency_response(&mut self, mut frequency_response: FrequencyResponse) {
+        let visible_if = frequency_response.visible_if.take();
+        let enabled_if = frequency_response.enabled_if.take();
+
+        self.push_block(
+            Block::FrequencyResponse(FrequencyResponseBlock::new(frequency_response)),
+            visible_if,
+            enabled_if,
+        );
+    }
+
+    pub fn push_readout(&mut self, mut readout: Readout) {
+        let visible_if = readout.visible_if.take();
+        let enabled_if = readout.enabled_if.take();
+
+        let index = match self.output_names.iter().position(|n| *n == readout.label) {
+            Some(index) => index,
+            None => {
+                self.output_names.push(readout.label);
+                self.output_names.len() - 1
+            }
+        };
+
+        self.push_block(
+            Block::Readout(ReadoutBlock::new(index, readout)),
+            visible_if,
+            enabled_if,
+        );
+    }
+
+    pub fn load_audio_file(&mut self, path: &str, gain: f32) {
+        self.audio_file_path = Some(String::from(path));
+        self.audio_file_gain = gain;
+    }
+
+    /// Starts an OSC server on `127.0.0.1:<port>`, so tools on the same machine
+    /// can control the parameters and the transport. See `osc::spawn` for the
+    /// supported addresses.
+    pub fn enable_osc(&mut self, port: u16) {
+        self.osc_addr = Some(SocketAddr::from(([127, 0, 0, 1], port)));
+    }
+
+    /// Same as `enable_osc`, but on the given address, e.g. `0.0.0.0:9000` to
+    /// allow control from other machines.
+    pub fn enable_osc_on(&mut self, addr: SocketAddr) {
+        self.osc_addr = Some(addr);
+    }
+
+    /// Saves the parameter values, audio file, bypass state and play mode while
+    /// the app runs and restores them on the next launch of an app with the
+    /// same title. Launches with command-line overrides from `parse_args`
+    /// neither restore nor save the session.
+    pub fn remember_session(&mut self) {
+        self.remember_session = true;
+    }
+
+    /// Shows small meters of the input and output in the top bar, whichever
+    /// blocks are visible.
+    pub fn show_top_bar_meters(&mut self, scale: MeterScale) {
+        self.top_bar_meters = Some(scale);
+    }
+
+    fn add_key(&mut self, key: &'static str) {
+        if key.is_empty() {
+            panic!("Parameter {} has no label or key", self.next_id);
+        }
+        if key.trim() != key || key.contains(|c: char| c == '=' || c == '\n') {
+            panic!(
+                "Parameter key `{}` can't have leading or trailing whitespace, `=` or newlines",
+                key
+            );
+        }
+        if self.keys.contains(&key) {
+            panic!(
+                "Parameter key `{}` is used more than once, give one of the parameters a unique `key`",
+                key
+            );
+        }
+
+        self.keys.push(key);
+    }
+
+    /// Overrides the values set in code with the command-line arguments:
+    ///
+    /// ```text
+    /// --param "Input Gain=6"   sets a parameter by its label or key
+    /// --file path.wav          plays a different audio file
+    /// --gain 0.8               sets the gain of the audio file
+    /// --bypass                 starts bypassed
+    /// --autoplay               starts playing the audio file right away
+    /// --osc 9000               starts an OSC server on the given local port
+    /// ```
+    pub fn parse_args(&mut self) -> Result<(), String> {
+        self.parse_args_from(std::env::args().skip(1))
+    }
+
+    /// Same as `parse_args`, but with the given arguments.
+    pub fn parse_args_from<I: Iterator<Item = String>>(
+        &mut self,
+        mut args: I,
+    ) -> Result<(), String> {
+        if self.code_defaults.is_none() {
+            self.code_defaults = Some(self.session());
+        }
+
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--param" => {
+                    let param = args
+                        .next()
+                        .ok_or("--param needs a value such as \"Input Gain=6\"")?;
+                    self.set_param_arg(&param)?;
+                    self.has_arg_overrides = true;
+                }
+                "--file" => {
+                    let path = args.next().ok_or("--file needs a path")?;
+                    self.audio_file_path = Some(path);
+                    self.has_arg_overrides = true;
+                }
+                "--gain" => {
+                    let gain = args.next().ok_or("--gain needs a value")?;
+                    self.audio_file_gain = gain
+                        .parse::<f32>()
+                        .map_err(|_| format!("invalid gain `{}`", gain))?;
+                    self.has_arg_overrides = true;
+                }
+                "--bypass" => {
+                    self.bypassed = true;
+                    self.has_arg_overrides = true;
+                }
+                "--autoplay" => {
+                    self.autoplay = true;
+                    self.has_arg_overrides = true;
+                }
+                "--osc" => {
+                    let port = args.next().ok_or("--osc needs a port")?;
+                    let port = port
+                        .parse::<u16>()
+                        .map_err(|_| format!("invalid OSC port `{}`", port))?;
+                    self.enable_osc(port);
+                }
+                _ => return Err(format!("unknown argument `{}`", arg)),
+            }
+        }
+
+        Ok(())
+    }
+
+    // sets a parameter from a `Label=value` argument
+    fn set_param_arg(&mut self, arg: &str) -> Result<(), String> {
+        let mut halves = arg.splitn(2, '=');
+        let name = halves.next().unwrap_or("").trim();
+        let text = halves
+            .next()
+            .ok_or_else(|| format!("invalid parameter `{}`, expected \"Label=value\"", arg))?;
+
+        let id = self
+            .param_id(name)
+            .or_else(|| {
+                self.blocks.iter().find_map(|block| {
+                    block
+                        .ids()
+                        .into_iter()
+                        .find(|id| block.param_label(*id) == Some(name))
+                })
+            })
+            .ok_or_else(|| format!("unknown parameter `{}`", name))?;
+
+        let value = self
+            .blocks
+            .iter()
+            .find_map(|block| block.parse_value(id, text))
+            .ok_or_else(|| format!("invalid value `{}` for parameter `{}`", text, name))?;
+        self.set_param(Param { id, value });
+
+        Ok(())
+    }
+
+    fn session(&self) -> session::Session {
+        let mut params: Vec<Param> = Vec::new();
+        for block in self.blocks.iter() {
+            block.add_params(&mut params);
+        }
+
+        session::Session {
+            params: params
+                .iter()
+                .map(|param| (String::from(self.keys[param.id as usize]), param.value))
+                .collect(),
+            audio_file_path: self.audio_file_path.clone(),
+            audio_file_gain: self.audio_file_gain,
+            bypassed: self.bypassed,
+            playing: self.autoplay,
+        }
+    }
+
+    fn restore_session(&mut self, session: &session::Session) {
+        for (key, value) in session.params.iter() {
+            if let Some(id) = self.param_id(key) {
+                self.set_param(Param { id, value: *value });
+            }
+        }
+
+        self.audio_file_path = session.audio_file_path.clone();
+        self.audio_file_gain = session.audio_file_gain;
+        self.bypassed = session.bypassed;
+        self.autoplay = session.playing;
+    }
+
+    // conditions may refer to parameters pushed after their block, so this runs at startup
+    fn check_conditions(&self) {
+        for conditions in self.block_conditions.iter() {
+            let both = conditions
+                .visible_if
+                .iter()
+                .chain(conditions.enabled_if.iter());
+            for condition in both {
+                if self.param_id(condition.key()).is_none() {
+                    panic!(
+                        "Condition refers to unknown parameter key `{}`",
+                        condition.key()
+                    );
+                }
+            }
+        }
+    }
+
+    // sets a saved or typed value, which the block clamps or snaps to the values it can
+    // take, e.g. when the options of an option knob changed since it was saved
+    fn set_param(&mut self, param: Param) {
+        for block in self.blocks.iter_mut() {
+            block.set_param(param);
+        }
+    }
+
+    /// Returns the internal id of the parameter with the given key.
+    fn param_id(&self, key: &str) -> Option<u32> {
+        self.keys.iter().position(|k| *k == key).map(|id| id as u32)
+    }
+}
+
+impl Default for GuiSetup {
+    fn default() -> Self {
+        Self {
+            title: String::from("DSP Box"),
+            blocks: Vec::new(),
+            block_conditions: Vec::new(),
+            audio_file_path: None,
+            audio_file_gain: 1.0,
+            next_id: 0,
+            bypassed: false,
+            autoplay: false,
+            osc_addr: None,
+            remember_session: false,
+            has_arg_overrides: false,
+            code_defaults: None,
+            keys: Vec::new(),
+            output_names: Vec::new(),
+            trigger_names: Vec::new(),
+            top_bar_meters: None,
+            num_scopes: 0,
+            num_spectrums: 0,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum Message {
+    Tick(Instant),
+    EventOccurred(iced_native::Event),
+    ParamMoved(u32),
+    PlayPauseBtnPressed,
+    StopBtnPressed,
+    BypassBtnPressed,
+    PanicBtnPressed,
+    OptionSelected(u32, usize),
+    TriggerBtnPressed(usize),
+    ClipBtnPressed(MeterSource),
+    ScopeCtrlPressed(usize, ScopeCtrl),
+    SpectrumCtrlPressed(usize, SpectrumCtrl),
+    XYPadDoubleClicked(u32),
+    SnapshotBtnPressed,
+    CopyAToBBtnPressed,
+    RandomizeBtnPressed,
+    ResetAllBtnPressed,
+    RevertBtnPressed,
+    BlockRightClicked(u32),
+    CloseBlockMenu,
+    MidiLearnBtnPressed(u32),
+    MidiUnlearnBtnPressed(u32),
+    MidiMapBtnPressed,
+    RemoveMidiMappingBtnPressed(usize),
+    ValueTextClicked(u32),
+    ValueTextEdited(u32, String),
+    ValueTextSubmitted(u32),
+    ModulationBtnPressed(u32),
+    ModulationCtrlPressed(u32, ModCtrl),
+    ClearAutomationBtnPressed(u32),
+    RecordBtnPressed,
+    SavePresetBtnPressed,
+    LoadPresetBtnPressed,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum Snapshot {
+    A,
+    B,
+}
+
+struct DSPBoxGUI {
+    gui_setup: GuiSetup,
+    current: Instant,
+    theme: style::Theme,
+    gui_to_audio_tx: mpsc::Sender<GuiToAudioMsg>,
+    audio_to_gui: Arc<MessageRing>,
+    transport_pos: Arc<AtomicUsize>,
+    output_values: Arc<OutputValues>,
+    levels: Arc<Levels>,
+    meters: Meters,
+    scope: Arc<ScopeBuffer>,
+    modulated_normals: Arc<ModulatedNormals>,
+    retired_rx: mpsc::Receiver<Retired>,
+    osc_rx: Option<mpsc::Receiver<osc::OscCommand>>,
+    play_pause_btn: button::State,
+    stop_btn: button::State,
+    bypass_btn: button::State,
+    panic_btn: button::State,
+    snapshot_btn: button::State,
+    copy_a_to_b_btn: button::State,
+    randomize_btn: button::State,
+    reset_all_btn: button::State,
+    revert_btn: button::State,
+    midi_map_btn: button::State,
+    midi_mapping_btns: Vec<button::State>,
+    record_btn: button::State,
+    save_preset_btn: button::State,
+    load_preset_btn: button::State,
+    in_clip_btn: button::State,
+    out_clip_btn: button::State,
+    play_pause_btn_stopped: bool,
+    bypassed: bool,
+    audio_file_loaded: bool,
+    active_snapshot: Snapshot,
+    snapshot_a: Vec<Param>,
+    snapshot_b: Vec<Param>,
+    history: history::History,
+    open_block_menu: Option<u32>,
+    midi_map: midi::MidiMap,
+    midi_learn_target: Option<u32>,
+    show_midi_map: bool,
+    automation: automation::Automation,
+    code_defaults: session::Session,
+    session_path: Option<std::path::PathBuf>,
+    saved_session: Option<session::Session>,
+    last_session_save: Instant,
+}
+
+impl DSPBoxGUI {
+    pub fn update(&mut self, now: Instant) {
+        self.current = now;
+    }
+
+    fn current_params(&self) -> Vec<Param> {
+        let mut params: Vec<Param> = Vec::new();
+        for block in self.gui_setup.blocks.iter() {
+            block.add_params(&mut params);
+        }
+        params
+    }
+
+    // sets the values of all blocks and sends them to the audio thread in one message
+    fn load_params(&mut self, params: Vec<Param>) {
+        for param in params.iter() {
+            for block in self.gui_setup.blocks.iter_mut() {
+                block.set_param(*param);
+            }
+        }
+
+        self.gui_to_audio_tx
+            .send(GuiToAudioMsg::LoadParams(params))
+            .unwrap();
+    }
+
+    fn param_value(&self, id: u32) -> Option<f32> {
+        self.gui_setup
+            .blocks
+            .iter()
+            .find_map(|block| block.param_value(id))
+    }
+
+    fn condition_met(&self, condition: &Option<Condition>) -> bool {
+        match condition {
+            Some(condition) => self
+                .gui_setup
+                .param_id(condition.key())
+                .and_then(|id| self.param_value(id))
+                .map(|value| condition.is_met(value))
+                .unwrap_or(true),
+            None => true,
+        }
+    }
+
+    // sets the values of the given blocks and notifies the audio thread of each change
+    fn restore_params(&mut self, params: Vec<Param>) {
+        for param in params {
+            for block in self.gui_setup.blocks.iter_mut() {
+                block.set_param(param);
+            }
+
+            self.gui_to_audio_tx
+                .send(GuiToAudioMsg::ParamChanged(param))
+                .unwrap();
+        }
+    }
+
+    fn undo(&mut self) {
+        if let Some(params) = self.history.undo() {
+            self.restore_params(params);
+        }
+    }
+
+    fn redo(&mut self) {
+        if let Some(params) = self.history.redo() {
+            self.restore_params(params);
+        }
+    }
+
+    fn poll_audio_messages(&mut self) {
+        while let Some(msg) = self.audio_to_gui.pop() {
+            match msg {
+                AudioToGuiMsg::MidiCC { channel, cc, value } => {
+                    self.midi_cc_received(channel, cc, value);
+                }
+                AudioToGuiMsg::ParamChanged(param) => {
+                    self.param_set_by_dsp(param);
+                }
+            }
+        }
+
+        // memory the audio thread is done with is freed here instead
+        for _ in self.retired_rx.try_iter() {}
+    }
+
+    fn param_set_by_dsp(&mut self, param: Param) {
+        for block in self.gui_setup.blocks.iter_mut() {
+            block.set_param(param);
+        }
+
+        // the block may have clamped or snapped the value, so keep the audio thread in sync
+        if let Some(value) = self.param_value(param.id) {
+            if value != param.value {
+                self.gui_to_audio_tx
+                    .send(GuiToAudioMsg::ParamChanged(Param {
+                        id: param.id,
+                        value,
+                    }))
+                    .unwrap();
+            }
+        }
+    }
+
+    fn midi_cc_received(&mut self, channel: u8, cc: u8, value: u8) {
+        if let Some(param_id) = self.midi_learn_target.take() {
+            self.midi_map.learn(channel, cc, param_id);
+            self.midi_map.save();
+        }
+
+        let normal = iced_audio::Normal::new(f32::from(value) / 127.0);
+
+        for param_id in self.midi_map.param_ids(channel, cc) {
+            let old_value = self.param_value(param_id);
+
+            for block in self.gui_setup.blocks.iter_mut() {
+                if let Some(param) = block.set_normal(param_id, normal) {
+                    self.gui_to_audio_tx
+                        .send(GuiToAudioMsg::ParamChanged(param))
+                        .unwrap();
+
+                    // undone like a move from OSC
+                    if let Some(old_value) = old_value {
+                        self.history.record_remote(
+                            param.id,
+                            old_value,
+                            param.value,
+                            Instant::now(),
+                        );
+                    }
+                }
+            }
+
+            self.record_automation(param_id);
+        }
+    }
+
+    fn poll_osc_messages(&mut self) {
+        let commands: Vec<osc::OscCommand> = match &self.osc_rx {
+            Some(osc_rx) => osc_rx.try_iter().collect(),
+            None => return,
+        };
+
+        for command in commands {
+            match command {
+                osc::OscCommand::SetParam(param) => {
+                    self.remote_param_changed(param);
+                }
+                osc::OscCommand::Play => {
+                    if self.play_pause_btn_stopped {
+                        self.play_pause();
+                    }
+                }
+                osc::OscCommand::Pause => {
+                    if !self.play_pause_btn_stopped {
+                        self.play_pause();
+                    }
+                }
+                osc::OscCommand::Stop => {
+                    self.stop();
+                }
+                osc::OscCommand::Bypass(bypassed) => {
+                    self.set_bypassed(bypassed);
+                }
+                osc::OscCommand::Panic => {
+                    self.panic();
+                }
+            }
+        }
+    }
+
+    // applies a parameter change from outside of the GUI the same way as a knob move
+    fn remote_param_changed(&mut self, param: Param) {
+        let old_value = self.param_value(param.id);
+
+        for block in self.gui_setup.blocks.iter_mut() {
+            block.set_param(param);
+        }
+
+        if let (Some(old_value), Some(new_value)) = (old_value, self.param_value(param.id)) {
+            self.gui_to_audio_tx
+                .send(GuiToAudioMsg::ParamChanged(Param {
+                    id: param.id,
+                    value: new_value,
+                }))
+                .unwrap();
+
+            self.history
+                .record_remote(param.id, old_value, new_value, Instant::now());
+        }
+
+        self.record_automation(param.id);
+    }
+
+    fn play_pause(&mut self) {
+        if self.audio_file_loaded {
+            if self.play_pause_btn_stopped {
+                self.gui_to_audio_tx.send(GuiToAudioMsg::Play).unwrap();
+            } else {
+                self.gui_to_audio_tx.send(GuiToAudioMsg::Pause).unwrap();
+            }
+
+            self.play_pause_btn_stopped = !self.play_pause_btn_stopped;
+        }
+    }
+
+    fn stop(&mut self) {
+        if self.audio_file_loaded {
+            self.play_pause_btn_stopped = true;
+            self.gui_to_audio_tx.send(GuiToAudioMsg::Stop).unwrap();
+        }
+    }
+
+    fn set_bypassed(&mut self, bypassed: bool) {
+        if bypassed != self.bypassed {
+            if bypassed {
+                self.gui_to_audio_tx.send(GuiToAudioMsg::Bypass).unwrap();
+            } else {
+                self.gui_to_audio_tx.send(GuiToAudioMsg::Unbypass).unwrap();
+            }
+
+            self.bypassed = bypassed;
+        }
+    }
+
+    fn panic(&mut self) {
+        self.play_pause_btn_stopped = true;
+        self.gui_to_audio_tx.send(GuiToAudioMsg::Panic).unwrap();
+    }
+
+    fn is_playing(&self) -> bool {
+        self.audio_file_loaded && !self.play_pause_btn_stopped
+    }
+
+    // records the current value of a parameter the user just moved if recording is armed
+    fn record_automation(&mut self, id: u32) {
+        if !self.automation.armed() || !self.is_playing() {
+            return;
+        }
+
+        if let Some(value) = self.param_value(id) {
+            let frame = self.transport_pos.load(Ordering::Relaxed);
+            self.automation.record(id, value, frame, Instant::now());
+
+            self.send_automation_lane(id);
+        }
+    }
+
+    fn send_automation_lane(&self, id: u32) {
+        self.gui_to_audio_tx
+            .send(GuiToAudioMsg::SetAutomationLane {
+                id,
+                points: self.automation.lane(id).points().to_vec(),
+            })
+            .unwrap();
+    }
+
+    // moves the knobs along with the automation the audio thread is playing back
+    fn follow_automation(&mut self) {
+        if !self.is_playing() {
+            return;
+        }
+
+        let frame = self.transport_pos.load(Ordering::Relaxed);
+        let now = Instant::now();
+
+        for (id, lane) in self.automation.lanes().iter().enumerate() {
+            let id = id as u32;
+            if self.automation.is_touched(id, now) {
+                continue;
+            }
+
+            if let Some(value) = lane.value_at(frame) {
+                for block in self.gui_setup.blocks.iter_mut() {
+                    block.set_param(Param { id, value });
+                }
+            }
+        }
+    }
+
+    fn preset_path(&self) -> std::path::PathBuf {
+        config::app_file_path(&self.gui_setup.title, "preset")
+    }
+
+    fn save_preset(&self) {
+        let keys = &self.gui_setup.keys;
+
+        let preset = preset::Preset {
+            params: self
+                .current_params()
+                .iter()
+                .map(|param| (String::from(keys[param.id as usize]), param.value))
+                .collect(),
+            lanes: self
+                .automation
+                .lanes()
+                .iter()
+                .enumerate()
+                .map(|(id, lane)| (String::from(keys[id]), lane.clone()))
+                .collect(),
+        };
+
+        match preset.save(&self.preset_path()) {
+            Ok(()) => println!("Saved preset to {:?}", self.preset_path()),
+            Err(e) => eprintln!("{}", e),
+        }
+    }
+
+    fn load_preset(&mut self) {
+        let preset = match preset::Preset::load(&self.preset_path()) {
+            Ok(preset) => preset,
+            Err(e) => {
+                eprintln!("{}", e);
+                return;
+            }
+        };
+
+        let num_lanes = self.automation.lanes().len() as u32;
+        for id in 0..num_lanes {
+            self.automation.clear_lane(id);
+        }
+        for (key, lane) in preset.lanes {
+            match self.gui_setup.param_id(&key) {
+                Some(id) => self.automation.set_lane(id, lane),
+                None => eprintln!("ignoring automation of unknown parameter `{}`", key),
+            }
+        }
+        for id in 0..num_lanes {
+            self.send_automation_lane(id);
+        }
+
+        let old_params = self.current_params();
+        for (key, value) in preset.params {
+            let id = match self.gui_setup.param_id(&key) {
+                Some(id) => id,
+                None => {
+                    eprintln!("ignoring unknown parameter `{}` in preset", key);
+                    continue;
+                }
+            };
+
+            self.gui_setup.set_param(Param { id, value });
+        }
+        self.commit_block_changes(old_params);
+    }
+
+    fn randomize(&mut self) {
+        let old_params = self.current_params();
+
+        let mut rng = rand::thread_rng();
+        for block in self.gui_setup.blocks.iter_mut() {
+            block.randomize(&mut rng);
+        }
+
+        self.commit_block_changes(old_params);
     }
 
-    pub fn int(min: i32, max: i32) -> Self {
-        Range::Int(IntRange::new(min, max))
-    }
+    fn reset_all(&mut self) {
+        let old_params = self.current_params();
 
-    pub fn db(min: f32, max: f32, zero_db_pos: ZeroDBPos) -> Self {
-        let zero_pos = match zero_db_pos {
-            ZeroDBPos::Center => iced_audio::Normal::center(),
-            ZeroDBPos::Min => iced_audio::Normal::min(),
-            ZeroDBPos::Max => iced_audio::Normal::max(),
-            ZeroDBPos::ThreeFourths => iced_audio::Normal::new(0.75),
-        };
-        Range::DB(DBRange::new(min, max, zero_pos))
-    }
+        for block in self.gui_setup.blocks.iter_mut() {
+            block.reset();
+        }
 
-    pub fn freq(min: f32, max: f32) -> Self {
-        Range::Freq(FreqRange::new(min, max))
+        self.commit_block_changes(old_params);
     }
-}
-
-pub enum ZeroDBPos {
-    Center,
-    Min,
-    Max,
-    ThreeFourths,
-}
 
-impl Default for ZeroDBPos {
-    fn default() -> Self {
-        ZeroDBPos::Center
-    }
-}
+    // restores the parameters, audio file and bypass state set in `main()`
+    fn revert_to_code_defaults(&mut self) {
+        let defaults = self.code_defaults.clone();
 
-pub struct Knob {
-    pub label: &'static str,
-    pub value: f32,
-    pub default_value: f32,
-    pub range: Range,
-}
+        let audio_file_changed = defaults.audio_file_path != self.gui_setup.audio_file_path
+            || defaults.audio_file_gain != self.gui_setup.audio_file_gain;
 
-pub struct OptionKnob {
-    pub label: &'static str,
-    pub value: u32,
-    pub default_value: u32,
-    pub options: Vec<String>,
-}
+        let old_params = self.current_params();
+        self.gui_setup.restore_session(&defaults);
+        self.commit_block_changes(old_params);
 
-struct Flags {
-    pub gui_setup: GuiSetup,
-    pub gui_to_audio_tx: mpsc::Sender<GuiToAudioMsg>,
-}
+        self.set_bypassed(defaults.bypassed);
 
-impl Default for Flags {
-    fn default() -> Self {
-        let (tx, _) = mpsc::channel();
+        if audio_file_changed {
+            self.stop();
 
-        Self {
-            gui_setup: Default::default(),
-            gui_to_audio_tx: tx,
+            self.audio_file_loaded = match &defaults.audio_file_path {
+                Some(audio_file_path) => send_audio_file(
+                    audio_file_path,
+                    defaults.audio_file_gain,
+                    &self.gui_to_audio_tx,
+                ),
+                None => false,
+            };
         }
     }
-}
 
-pub struct GuiSetup {
-    title: String,
-    blocks: Vec<Block>,
-    audio_file_path: Option<String>,
-    audio_file_gain: f32,
-    next_id: u32,
-}
+    // saves the session if it is remembered and has changed since it was last saved
+    fn save_session(&mut self) {
+        let session_path = match &self.session_path {
+            Some(session_path) => session_path,
+            None => return,
+        };
 
-impl GuiSetup {
-    pub fn new(title: &str) -> Self {
-        Self {
-            title: String::from(title),
-            blocks: Vec::new(),
-            audio_file_path: None,
-            audio_file_gain: 1.0,
-            next_id: 0,
+        let session = session::Session {
+            bypassed: self.bypassed,
+            playing: self.is_playing(),
+            ..self.gui_setup.session()
+        };
+
+        if self.saved_session.as_ref() != Some(&session) {
+            session.save(session_path);
+            self.saved_session = Some(session);
         }
     }
 
-    pub fn push_knob(&mut self, knob: Knob) {
-        self.blocks.push(Block::Knob(KnobBlock::new(
-            self.next_id,
-            knob.value,
-            knob.default_value,
-            knob.label.clone(),
-            knob.range,
-        )));
+    // records blocks that were changed in place as one undo entry and sends the new values
+    fn commit_block_changes(&mut self, old_params: Vec<Param>) {
+        let new_params = self.current_params();
 
-        self.next_id += 1;
+        self.history.record_all(&old_params, &new_params);
+
+        self.gui_to_audio_tx
+            .send(GuiToAudioMsg::LoadParams(new_params))
+            .unwrap();
     }
 
-    pub fn push_option_knob(&mut self, knob: OptionKnob) {
-        self.blocks.push(Block::OptionKnob(OptionKnobBlock::new(
-            self.next_id,
-            knob.value as i32,
-            knob.default_value as i32,
-            knob.label.clone(),
-            knob.options,
-        )));
+    fn switch_snapshot(&mut self) {
+        let current = self.current_params();
 
-        self.next_id += 1;
-    }
+        let recalled = match self.active_snapshot {
+            Snapshot::A => {
+                self.snapshot_a = current;
+                self.active_snapshot = Snapshot::B;
+                self.snapshot_b.clone()
+            }
+            Snapshot::B => {
+                self.snapshot_b = current;
+                self.active_snapshot = Snapshot::A;
+                self.snapshot_a.clone()
+            }
+        };
 
-    pub fn load_audio_file(&mut self, path: &str, gain: f32) {
-        self.audio_file_path = Some(String::from(path));
-        self.audio_file_gain = gain;
+        self.load_params(recalled);
     }
-}
 
-impl Default for GuiSetup {
-    fn default() -> Self {
-        Self {
-            title: String::from("DSP Box"),
-            blocks: Vec::new(),
-            audio_file_path: None,
-            audio_file_gain: 1.0,
-            next_id: 0,
+    fn copy_a_to_b(&mut self) {
+        match self.active_snapshot {
+            Snapshot::A => {
+                // A is live, so its latest values are the ones on screen
+                self.snapshot_b = self.current_params();
+            }
+            Snapshot::B => {
+                self.snapshot_b = self.snapshot_a.clone();
+                self.load_params(self.snapshot_a.clone());
+            }
         }
     }
 }
 
-#[derive(Debug, Copy, Clone)]
-pub enum Message {
-    Tick(Instant),
-    ParamMoved(u32),
-    PlayPauseBtnPressed,
-    StopBtnPressed,
-    BypassBtnPressed,
-    PanicBtnPressed,
-}
-
-struct DSPBoxGUI {
-    gui_setup: GuiSetup,
-    current: Instant,
-    theme: style::Theme,
-    gui_to_audio_tx: mpsc::Sender<GuiToAudioMsg>,
-    play_pause_btn: button::State,
-    stop_btn: button::State,
-    bypass_btn: button::State,
-    panic_btn: button::State,
-    play_pause_btn_stopped: bool,
-    bypassed: bool,
-    audio_file_loaded: bool,
+// the event loop drops the app when the window is closed, which catches the changes made
+// since the session was last saved on a tick
+impl Drop for DSPBoxGUI {
+    fn drop(&mut self) {
+        self.save_session();
+    }
 }
 
-impl DSPBoxGUI {
-    pub fn update(&mut self, now: Instant) {
-        self.current = now;
+// loads the audio file and sends it to the audio thread, returns false if it couldn't be loaded
+fn send_audio_file(path: &str, gain: f32, gui_to_audio_tx: &mpsc::Sender<GuiToAudioMsg>) -> bool {
+    match audio_player::AudioPlayerBuffer::new(path, 4096, gain) {
+        Ok(buffer) => {
+            gui_to_audio_tx
+                .send(GuiToAudioMsg::LoadAudioPlayerBuffer(buffer))
+                .unwrap();
+            true
+        }
+        Err(e) => {
+            eprintln!("{:?}", e);
+            false
+        }
     }
 }
 
@@ -219,109 +1532,344 @@ impl Application for DSPBoxGUI {
         self.gui_setup.title.clone()
     }
 
-    fn new(flags: Flags) -> (Self, Command<Message>) {
+    fn new(mut flags: Flags) -> (Self, Command<Message>) {
+        flags.gui_setup.check_conditions();
+
+        let code_defaults = match flags.gui_setup.code_defaults.take() {
+            Some(code_defaults) => code_defaults,
+            None => flags.gui_setup.session(),
+        };
+
+        let remember_session =
+            flags.gui_setup.remember_session && !flags.gui_setup.has_arg_overrides;
+        let session_path = if remember_session {
+            Some(config::app_file_path(&flags.gui_setup.title, "session"))
+        } else {
+            None
+        };
+        if let Some(session) = session_path.as_ref().and_then(session::Session::load) {
+            flags.gui_setup.restore_session(&session);
+        }
+
         let mut init_preset: Vec<Param> = Vec::new();
         for block in flags.gui_setup.blocks.iter() {
-            match block {
-                Block::Knob(block) => block.add_params(&mut init_preset),
-                Block::OptionKnob(block) => block.add_params(&mut init_preset),
-            }
+            block.add_params(&mut init_preset);
         }
         flags
             .gui_to_audio_tx
-            .send(GuiToAudioMsg::InitPreset(init_preset))
+            .send(GuiToAudioMsg::InitPreset(init_preset.clone()))
             .unwrap();
+        for block in flags.gui_setup.blocks.iter() {
+            block.send_modulation(&flags.gui_to_audio_tx);
+        }
 
-        let mut audio_file_loaded = false;
-        if let Some(audio_file_path) = &flags.gui_setup.audio_file_path {
-            let audio_player_buffer = audio_player::AudioPlayerBuffer::new(
-                &audio_file_path,
-                4096,
+        let audio_file_loaded = match &flags.gui_setup.audio_file_path {
+            Some(audio_file_path) => send_audio_file(
+                audio_file_path,
                 flags.gui_setup.audio_file_gain,
-            );
+                &flags.gui_to_audio_tx,
+            ),
+            None => false,
+        };
 
-            match audio_player_buffer {
-                Ok(buffer) => {
-                    audio_file_loaded = true;
+        let bypassed = flags.gui_setup.bypassed;
+        if bypassed {
+            flags.gui_to_audio_tx.send(GuiToAudioMsg::Bypass).unwrap();
+        }
 
-                    flags
-                        .gui_to_audio_tx
-                        .send(GuiToAudioMsg::LoadAudioPlayerBuffer(buffer))
-                        .unwrap();
-                }
-                Err(e) => {
-                    eprintln!("{:?}", e);
-                }
-            }
+        let autoplay = audio_file_loaded && flags.gui_setup.autoplay;
+        if autoplay {
+            flags.gui_to_audio_tx.send(GuiToAudioMsg::Play).unwrap();
         }
 
+        let osc_rx = flags.gui_setup.osc_addr.and_then(|addr| {
+            let keys = &flags.gui_setup.keys;
+            let params = flags
+                .gui_setup
+                .blocks
+                .iter()
+                .flat_map(|block| {
+                    block.ids().into_iter().filter_map(move |id| {
+                        let (min, max, default) = block.value_range(id)?;
+
+                        Some(osc::OscParam {
+                            id,
+                            key: String::from(keys[id as usize]),
+                            min,
+                            max,
+                            default,
+                        })
+                    })
+                })
+                .collect();
+
+            osc::spawn(addr, params)
+        });
+
+        let num_params = init_preset.len();
+
+        let midi_map = midi::MidiMap::load(
+            config::app_file_path(&flags.gui_setup.title, "midimap"),
+            flags.gui_setup.keys.clone(),
+        );
+
         (
             Self {
                 gui_setup: flags.gui_setup,
                 current: Instant::now(),
                 theme: style::Theme::Dark,
                 gui_to_audio_tx: flags.gui_to_audio_tx,
+                audio_to_gui: flags.shared.audio_to_gui,
+                transport_pos: flags.shared.transport_pos,
+                output_values: flags.shared.output_values,
+                levels: flags.shared.levels,
+                meters: Meters::new(),
+                scope: flags.shared.scope,
+                modulated_normals: flags.shared.modulated_normals,
+                retired_rx: flags.retired_rx,
+                osc_rx,
                 play_pause_btn: button::State::new(),
                 stop_btn: button::State::new(),
                 bypass_btn: button::State::new(),
                 panic_btn: button::State::new(),
-                play_pause_btn_stopped: true,
-                bypassed: false,
+                snapshot_btn: button::State::new(),
+                copy_a_to_b_btn: button::State::new(),
+                randomize_btn: button::State::new(),
+                reset_all_btn: button::State::new(),
+                revert_btn: button::State::new(),
+                midi_map_btn: button::State::new(),
+                midi_mapping_btns: Vec::new(),
+                record_btn: button::State::new(),
+                save_preset_btn: button::State::new(),
+                load_preset_btn: button::State::new(),
+                in_clip_btn: button::State::new(),
+                out_clip_btn: button::State::new(),
+                play_pause_btn_stopped: !autoplay,
+                bypassed,
                 audio_file_loaded,
+                active_snapshot: Snapshot::A,
+                snapshot_a: init_preset.clone(),
+                snapshot_b: init_preset,
+                history: history::History::new(),
+                open_block_menu: None,
+                midi_map,
+                midi_learn_target: None,
+                show_midi_map: false,
+                automation: automation::Automation::new(num_params),
+                code_defaults,
+                session_path,
+                saved_session: None,
+                last_session_save: Instant::now(),
             },
             Command::none(),
         )
     }
 
     fn subscription(&self) -> Subscription<Message> {
-        time::every(std::time::Duration::from_millis(10)).map(|instant| Message::Tick(instant))
+        Subscription::batch(vec![
+            time::every(std::time::Duration::from_millis(10)).map(|instant| Message::Tick(instant)),
+            iced_native::subscription::events().map(Message::EventOccurred),
+        ])
     }
 
     fn update(&mut self, message: Message) -> Command<Message> {
         match message {
             Message::Tick(instant) => {
+                let delta_secs = instant
+                    .saturating_duration_since(self.current)
+                    .as_secs_f32();
+                self.meters.update(&self.levels, delta_secs);
+
                 self.update(instant);
 
-                // Normally you would animate the meter here, but basic
-                // knobs are used instead for demonstration.
-            }
-            Message::ParamMoved(_id) => {
+                self.poll_audio_messages();
+                self.poll_osc_messages();
+                if self.current.duration_since(self.last_session_save) >= SESSION_SAVE_INTERVAL {
+                    self.last_session_save = self.current;
+                    self.save_session();
+                }
+                self.follow_automation();
+
+                let mut param_values = vec![0.0; self.gui_setup.next_id as usize];
+                for param in self.current_params() {
+                    param_values[param.id as usize] = param.value;
+                }
+
                 for block in self.gui_setup.blocks.iter_mut() {
-                    match block {
-                        Block::Knob(block) => block.update(message, &self.gui_to_audio_tx),
-                        Block::OptionKnob(block) => block.update(message, &self.gui_to_audio_tx),
-                    }
+                    block.update_output(
+                        &self.output_values,
+                        &self.meters,
+                        &self.scope,
+                        &param_values,
+                    );
+                    block.update_modulation(&self.modulated_normals);
                 }
             }
-            Message::PlayPauseBtnPressed => {
-                if self.audio_file_loaded {
-                    if self.play_pause_btn_stopped {
-                        self.gui_to_audio_tx.send(GuiToAudioMsg::Play).unwrap();
-                    } else {
-                        self.gui_to_audio_tx.send(GuiToAudioMsg::Pause).unwrap();
+            Message::EventOccurred(event) => match event {
+                iced_native::Event::Keyboard(keyboard::Event::KeyPressed {
+                    key_code: keyboard::KeyCode::Z,
+                    modifiers,
+                }) => {
+                    // typing a value must not revert a parameter
+                    let editing_text = self
+                        .gui_setup
+                        .blocks
+                        .iter()
+                        .any(|block| block.is_editing_text());
+
+                    if modifiers.control && !editing_text {
+                        if modifiers.shift {
+                            self.redo();
+                        } else {
+                            self.undo();
+                        }
+                    }
+                }
+                iced_native::Event::Keyboard(keyboard::Event::KeyPressed {
+                    key_code: keyboard::KeyCode::Escape,
+                    ..
+                }) => {
+                    for block in self.gui_setup.blocks.iter_mut() {
+                        block.cancel_text_edit();
                     }
+                }
+                iced_native::Event::Mouse(mouse::Event::ButtonPressed(_)) => {
+                    // a drag starts with the mouse button
+                    self.history.end_gesture();
+
+                    // the text field has already handled the click
+                    for block in self.gui_setup.blocks.iter_mut() {
+                        block.cancel_unfocused_text_edit();
+                    }
+                }
+                iced_native::Event::Mouse(mouse::Event::ButtonReleased(_)) => {
+                    self.history.end_gesture();
+                }
+                _ => {}
+            },
+            Message::ParamMoved(id)
+            | Message::OptionSelected(id, _)
+            | Message::ValueTextSubmitted(id) => {
+                let old_value = self.param_value(id);
+
+                for block in self.gui_setup.blocks.iter_mut() {
+                    block.update(message.clone(), &self.gui_to_audio_tx);
+                }
 
-                    self.play_pause_btn_stopped = !self.play_pause_btn_stopped;
+                if let (Some(old_value), Some(new_value)) = (old_value, self.param_value(id)) {
+                    self.history.record(id, old_value, new_value);
+                }
+                // typed values and picked options are complete edits on their own
+                if !matches!(message, Message::ParamMoved(_)) {
+                    self.history.end_gesture();
                 }
+
+                self.record_automation(id);
+            }
+            Message::PlayPauseBtnPressed => {
+                self.play_pause();
             }
             Message::StopBtnPressed => {
-                if self.audio_file_loaded {
-                    self.play_pause_btn_stopped = true;
-                    self.gui_to_audio_tx.send(GuiToAudioMsg::Stop).unwrap();
-                }
+                self.stop();
             }
             Message::BypassBtnPressed => {
-                if self.bypassed {
-                    self.gui_to_audio_tx.send(GuiToAudioMsg::Unbypass).unwrap();
-                } else {
-                    self.gui_to_audio_tx.send(GuiToAudioMsg::Bypass).unwrap();
+                self.set_bypassed(!self.bypassed);
+            }
+            Message::PanicBtnPressed => {
+                self.panic();
+            }
+            Message::XYPadDoubleClicked(id) => {
+                let old_params = self.current_params();
+
+                for block in self.gui_setup.blocks.iter_mut() {
+                    block.update(message.clone(), &self.gui_to_audio_tx);
                 }
 
-                self.bypassed = !self.bypassed;
+                self.commit_block_changes(old_params);
+
+                let ids = self
+                    .gui_setup
+                    .blocks
+                    .iter()
+                    .map(|block| block.ids())
+                    .find(|ids| ids.contains(&id))
+                    .unwrap_or_default();
+                for id in ids {
+                    self.record_automation(id);
+                }
             }
-            Message::PanicBtnPressed => {
-                self.play_pause_btn_stopped = true;
-                self.gui_to_audio_tx.send(GuiToAudioMsg::Panic).unwrap();
+            Message::ClipBtnPressed(source) => {
+                self.meters.reset_clips(source);
+            }
+            Message::ScopeCtrlPressed(..) | Message::SpectrumCtrlPressed(..) => {
+                for block in self.gui_setup.blocks.iter_mut() {
+                    block.update(message.clone(), &self.gui_to_audio_tx);
+                }
+            }
+            Message::TriggerBtnPressed(index) => {
+                self.gui_to_audio_tx
+                    .send(GuiToAudioMsg::Trigger(index))
+                    .unwrap();
+            }
+            Message::SnapshotBtnPressed => {
+                self.switch_snapshot();
+            }
+            Message::CopyAToBBtnPressed => {
+                self.copy_a_to_b();
+            }
+            Message::RandomizeBtnPressed => {
+                self.randomize();
+            }
+            Message::ResetAllBtnPressed => {
+                self.reset_all();
+            }
+            Message::RevertBtnPressed => {
+                self.revert_to_code_defaults();
+            }
+            Message::BlockRightClicked(id) => {
+                self.open_block_menu = Some(id);
+            }
+            Message::CloseBlockMenu => {
+                self.open_block_menu = None;
+            }
+            Message::MidiLearnBtnPressed(id) => {
+                self.open_block_menu = None;
+                self.midi_learn_target = Some(id);
+            }
+            Message::MidiUnlearnBtnPressed(id) => {
+                self.open_block_menu = None;
+                self.midi_map.unlearn(id);
+                self.midi_map.save();
+            }
+            Message::MidiMapBtnPressed => {
+                self.show_midi_map = !self.show_midi_map;
+            }
+            Message::RemoveMidiMappingBtnPressed(index) => {
+                self.midi_map.remove(index);
+                self.midi_map.save();
+            }
+            Message::ClearAutomationBtnPressed(id) => {
+                self.open_block_menu = None;
+                self.automation.clear_lane(id);
+                self.send_automation_lane(id);
+            }
+            Message::RecordBtnPressed => {
+                let armed = !self.automation.armed();
+                self.automation.set_armed(armed);
+            }
+            Message::SavePresetBtnPressed => {
+                self.save_preset();
+            }
+            Message::LoadPresetBtnPressed => {
+                self.load_preset();
+            }
+            Message::ValueTextClicked(_)
+            | Message::ValueTextEdited(_, _)
+            | Message::ModulationBtnPressed(_)
+            | Message::ModulationCtrlPressed(_, _) => {
+                for block in self.gui_setup.blocks.iter_mut() {
+                    block.update(message.clone(), &self.gui_to_audio_tx);
+                }
             }
         }
 
@@ -405,14 +1953,221 @@ impl Application for DSPBoxGUI {
         .on_press(Message::PanicBtnPressed)
         .style(self.theme.button());
 
+        let snapshot_btn = Button::new(
+            &mut self.snapshot_btn,
+            Text::new(match self.active_snapshot {
+                Snapshot::A => "A",
+                Snapshot::B => "B",
+            })
+            .size(16)
+            .horizontal_alignment(HorizontalAlignment::Center)
+            .vertical_alignment(VerticalAlignment::Center),
+        )
+        .width(Length::from(30))
+        .on_press(Message::SnapshotBtnPressed)
+        .style(self.theme.button());
+
+        let copy_a_to_b_btn = Button::new(
+            &mut self.copy_a_to_b_btn,
+            Text::new("A→B")
+                .size(16)
+                .horizontal_alignment(HorizontalAlignment::Center)
+                .vertical_alignment(VerticalAlignment::Center),
+        )
+        .width(Length::from(50))
+        .on_press(Message::CopyAToBBtnPressed)
+        .style(self.theme.button());
+
+        let randomize_btn = Button::new(
+            &mut self.randomize_btn,
+            Text::new("Rand")
+                .size(16)
+                .horizontal_alignment(HorizontalAlignment::Center)
+                .vertical_alignment(VerticalAlignment::Center),
+        )
+        .width(Length::from(50))
+        .on_press(Message::RandomizeBtnPressed)
+        .style(self.theme.button());
+
+        let reset_all_btn = Button::new(
+            &mut self.reset_all_btn,
+            Text::new("Reset")
+                .size(16)
+                .horizontal_alignment(HorizontalAlignment::Center)
+                .vertical_alignment(VerticalAlignment::Center),
+        )
+        .width(Length::from(50))
+        .on_press(Message::ResetAllBtnPressed)
+        .style(self.theme.button());
+
+        let revert_btn = Button::new(
+            &mut self.revert_btn,
+            Text::new("Revert")
+                .size(16)
+                .horizontal_alignment(HorizontalAlignment::Center)
+                .vertical_alignment(VerticalAlignment::Center),
+        )
+        .width(Length::from(60))
+        .on_press(Message::RevertBtnPressed)
+        .style(self.theme.button());
+
+        let record_btn = Button::new(
+            &mut self.record_btn,
+            Text::new("Rec")
+                .size(16)
+                .horizontal_alignment(HorizontalAlignment::Center)
+                .vertical_alignment(VerticalAlignment::Center),
+        )
+        .width(Length::from(40))
+        .on_press(Message::RecordBtnPressed)
+        .style(if self.automation.armed() {
+            self.theme.bypassed_button()
+        } else {
+            self.theme.button()
+        });
+
+        let save_preset_btn = Button::new(
+            &mut self.save_preset_btn,
+            Text::new("Save")
+                .size(16)
+                .horizontal_alignment(HorizontalAlignment::Center)
+                .vertical_alignment(VerticalAlignment::Center),
+        )
+        .width(Length::from(45))
+        .on_press(Message::SavePresetBtnPressed)
+        .style(self.theme.button());
+
+        let load_preset_btn = Button::new(
+            &mut self.load_preset_btn,
+            Text::new("Load")
+                .size(16)
+                .horizontal_alignment(HorizontalAlignment::Center)
+                .vertical_alignment(VerticalAlignment::Center),
+        )
+        .width(Length::from(45))
+        .on_press(Message::LoadPresetBtnPressed)
+        .style(self.theme.button());
+
+        let midi_map_btn = Button::new(
+            &mut self.midi_map_btn,
+            Text::new("MIDI")
+                .size(16)
+                .horizontal_alignment(HorizontalAlignment::Center)
+                .vertical_alignment(VerticalAlignment::Center),
+        )
+        .width(Length::from(50))
+        .on_press(Message::MidiMapBtnPressed)
+        .style(if self.midi_learn_target.is_some() {
+            self.theme.bypassed_button()
+        } else {
+            self.theme.button()
+        });
+
+        let midi_learn_text = if let Some(id) = self.midi_learn_target {
+            let label = self
+                .gui_setup
+                .blocks
+                .iter()
+                .find_map(|block| block.param_label(id))
+                .unwrap_or("");
+            format!("Move a MIDI controller to map `{}`", label)
+        } else {
+            String::new()
+        };
+
+        let mut midi_map_panel = Column::new().padding(8).spacing(3);
+        if self.show_midi_map {
+            let mappings = self.midi_map.mappings();
+            self.midi_mapping_btns
+                .resize_with(mappings.len(), button::State::new);
+
+            if mappings.is_empty() {
+                midi_map_panel = midi_map_panel
+                    .push(Text::new("No MIDI mappings. Right-click a knob to learn one.").size(14));
+            }
+
+            for (index, (mapping, btn_state)) in mappings
+                .iter()
+                .zip(self.midi_mapping_btns.iter_mut())
+                .enumerate()
+            {
+                let label = self
+                    .gui_setup
+                    .blocks
+                    .iter()
+                    .find_map(|block| block.param_label(mapping.param_id))
+                    .unwrap_or("(missing)");
+
+                let row = Row::new()
+                    .spacing(7)
+                    .align_items(Align::Center)
+                    .push(
+                        Text::new(format!(
+                            "CC {} (ch {}) → {}",
+                            mapping.cc,
+                            mapping.channel + 1,
+                            label
+                        ))
+                        .size(14)
+                        .width(Length::from(240)),
+                    )
+                    .push(
+                        Button::new(btn_state, Text::new("Unlearn").size(14))
+                            .on_press(Message::RemoveMidiMappingBtnPressed(index))
+                            .style(self.theme.button()),
+                    );
+
+                midi_map_panel = midi_map_panel.push(row);
+            }
+        }
+
         let mut blocks: Vec<Element<Message>> = Vec::new();
         blocks.reserve_exact(self.gui_setup.blocks.len());
 
-        for block in self.gui_setup.blocks.iter_mut() {
-            blocks.push(match block {
-                Block::Knob(block) => block.view(&self.theme),
-                Block::OptionKnob(block) => block.view(&self.theme),
-            });
+        // evaluated up front, since the conditions depend on the values of other blocks
+        let block_states: Vec<(bool, bool)> = self
+            .gui_setup
+            .block_conditions
+            .iter()
+            .map(|conditions| {
+                (
+                    self.condition_met(&conditions.visible_if),
+                    self.condition_met(&conditions.enabled_if),
+                )
+            })
+            .collect();
+
+        for (block, (visible, enabled)) in self.gui_setup.blocks.iter_mut().zip(block_states) {
+            if !visible {
+                continue;
+            }
+
+            let show_menu = enabled
+                && self
+                    .open_block_menu
+                    .map_or(false, |id| block.ids().contains(&id));
+            blocks.push(block.view(&self.theme, show_menu, enabled));
+        }
+
+        let mut top_bar_meters = Row::new().spacing(10).align_items(Align::Center);
+        if let Some(scale) = self.gui_setup.top_bar_meters {
+            top_bar_meters = top_bar_meters
+                .push(compact_meter(
+                    &self.theme,
+                    &mut self.in_clip_btn,
+                    "IN",
+                    &self.meters,
+                    MeterSource::Input,
+                    scale,
+                ))
+                .push(compact_meter(
+                    &self.theme,
+                    &mut self.out_clip_btn,
+                    "OUT",
+                    &self.meters,
+                    MeterSource::Output,
+                    scale,
+                ));
         }
 
         let top_bar = Container::new(
@@ -423,7 +2178,22 @@ impl Application for DSPBoxGUI {
                 .spacing(7)
                 .push(play_pause_btn)
                 .push(stop_btn)
+                .push(record_btn)
+                .push(top_bar_meters)
+                .push(
+                    Text::new(midi_learn_text)
+                        .size(14)
+                        .vertical_alignment(VerticalAlignment::Center),
+                )
                 .push(Space::with_width(Length::Fill))
+                .push(snapshot_btn)
+                .push(copy_a_to_b_btn)
+                .push(randomize_btn)
+                .push(reset_all_btn)
+                .push(revert_btn)
+                .push(save_preset_btn)
+                .push(load_preset_btn)
+                .push(midi_map_btn)
                 .push(bypass_btn)
                 .push(panic_btn),
         )
@@ -444,6 +2214,7 @@ impl Application for DSPBoxGUI {
             .padding(0)
             .spacing(0)
             .push(top_bar)
+            .push(midi_map_panel)
             .push(block_container);
 
         Container::new(content)
@@ -0,0 +1,157 @@
+use crate::audio_thread::AudioToGuiMsg;
+use crate::Param;
+
+use std::sync::atomic::{AtomicU32, AtomicUsize, Ordering};
+
+// the number of messages that fit between two GUI ticks
+static CAPACITY: usize = 1024;
+
+// the kind of message in a slot
+const MIDI_CC: u32 = 0;
+const PARAM_CHANGED: u32 = 1;
+
+/// The messages from the audio thread to the GUI. The audio thread pushes them
+/// without locking or allocating, and the GUI pops them on each tick. Messages
+/// are dropped while it is full.
+pub(crate) struct MessageRing {
+    // the kind of each message and its two fields
+    slots: Vec<[AtomicU32; 3]>,
+    // the number of messages pushed and popped so far
+    write_pos: AtomicUsize,
+    read_pos: AtomicUsize,
+}
+
+impl MessageRing {
+    pub(crate) fn new() -> Self {
+        Self {
+            slots: (0..CAPACITY)
+                .map(|_| [AtomicU32::new(0), AtomicU32::new(0), AtomicU32::new(0)])
+                .collect(),
+            write_pos: AtomicUsize::new(0),
+            read_pos: AtomicUsize::new(0),
+        }
+    }
+
+    /// Only the audio thread may push. Returns false if the message was dropped.
+    pub(crate) fn push(&self, msg: AudioToGuiMsg) -> bool {
+        let write_pos = self.write_pos.load(Ordering::Relaxed);
+        if write_pos - self.read_pos.load(Ordering::Acquire) >= CAPACITY {
+            return false;
+        }
+
+        let (kind, a, b) = match msg {
+            AudioToGuiMsg::MidiCC { channel, cc, value } => (
+                MIDI_CC,
+                u32::from(channel) << 16 | u32::from(cc) << 8 | u32::from(value),
+                0,
+            ),
+            AudioToGuiMsg::ParamChanged(param) => (PARAM_CHANGED, param.id, param.value.to_bits()),
+        };
+
+        let slot = &self.slots[write_pos % CAPACITY];
+        slot[0].store(kind, Ordering::Relaxed);
+        slot[1].store(a, Ordering::Relaxed);
+        slot[2].store(b, Ordering::Relaxed);
+
+        self.write_pos.store(write_pos + 1, Ordering::Release);
+
+        true
+    }
+
+    /// Only the GUI may pop.
+    pub(crate) fn pop(&self) -> Option<AudioToGuiMsg> {
+        let read_pos = self.read_pos.load(Ordering::Relaxed);
+        if read_pos == self.write_pos.load(Ordering::Acquire) {
+            return None;
+        }
+
+        let slot = &self.slots[read_pos % CAPACITY];
+        let kind = slot[0].load(Ordering::Relaxed);
+        let a = slot[1].load(Ordering::Relaxed);
+        let b = slot[2].load(Ordering::Relaxed);
+
+        // the slot may be reused from here on
+        self.read_pos.store(read_pos + 1, Ordering::Release);
+
+        Some(match kind {
+            MIDI_CC => AudioToGuiMsg::MidiCC {
+                channel: (a >> 16) as u8,
+                cc: (a >> 8) as u8,
+                value: a as u8,
+            },
+            _ => AudioToGuiMsg::ParamChanged(Param {
+                id: a,
+                value: f32::from_bits(b),
+            }),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn param_changed(id: u32) -> AudioToGuiMsg {
+        AudioToGuiMsg::ParamChanged(Param {
+            id,
+            value: id as f32 * -0.5,
+        })
+    }
+
+    fn popped_id(ring: &MessageRing) -> Option<u32> {
+        match ring.pop() {
+            Some(AudioToGuiMsg::ParamChanged(param)) => {
+                assert_eq!(param.value, param.id as f32 * -0.5);
+                Some(param.id)
+            }
+            Some(AudioToGuiMsg::MidiCC { .. }) => panic!("expected a parameter change"),
+            None => None,
+        }
+    }
+
+    #[test]
+    fn messages_come_out_in_order() {
+        let ring = MessageRing::new();
+        assert!(ring.push(param_changed(3)));
+        assert!(ring.push(AudioToGuiMsg::MidiCC {
+            channel: 15,
+            cc: 127,
+            value: 64,
+        }));
+        assert!(ring.push(param_changed(4)));
+
+        assert_eq!(popped_id(&ring), Some(3));
+        match ring.pop() {
+            Some(AudioToGuiMsg::MidiCC { channel, cc, value }) => {
+                assert_eq!((channel, cc, value), (15, 127, 64));
+            }
+            _ => panic!("expected a MIDI CC"),
+        }
+        assert_eq!(popped_id(&ring), Some(4));
+        assert_eq!(popped_id(&ring), None);
+    }
+
+    #[test]
+    fn full_ring_drops_new_messages() {
+        let ring = MessageRing::new();
+        for id in 0..CAPACITY as u32 {
+            assert!(ring.push(param_changed(id)));
+        }
+        assert!(!ring.push(param_changed(CAPACITY as u32)));
+
+        assert_eq!(popped_id(&ring), Some(0));
+        assert!(ring.push(param_changed(CAPACITY as u32)));
+    }
+
+    #[test]
+    fn slots_are_reused_after_wrapping() {
+        let ring = MessageRing::new();
+        for id in 0..CAPACITY as u32 * 3 {
+            assert!(ring.push(param_changed(id)));
+            assert!(ring.push(param_changed(id + 1)));
+            assert_eq!(popped_id(&ring), Some(id));
+            assert_eq!(popped_id(&ring), Some(id + 1));
+        }
+        assert_eq!(popped_id(&ring), None);
+    }
+}
@@ -0,0 +1,216 @@
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+
+// the measured channels, indexed like the arrays in `Levels` and `Meters`
+pub(crate) const IN_L: usize = 0;
+pub(crate) const IN_R: usize = 1;
+pub(crate) const OUT_L: usize = 2;
+pub(crate) const OUT_R: usize = 3;
+const NUM_CHANNELS: usize = 4;
+
+// the time constant of the RMS level
+pub(crate) static RMS_WINDOW_SECS: f32 = 0.3;
+
+// how long the peak hold marker stays before it falls back to the peak
+static PEAK_HOLD_SECS: f32 = 1.5;
+
+// how fast the displayed peak falls back
+static PEAK_FALL_DB_PER_SEC: f32 = 24.0;
+
+// the level shown for silence, below the floor of every scale
+static SILENCE_DB: f32 = -144.0;
+
+/// Which signal a meter measures.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum MeterSource {
+    /// The input of the app, i.e. the audio file while it plays and the live
+    /// input otherwise.
+    Input,
+    /// The output of the app.
+    Output,
+}
+
+impl MeterSource {
+    // the left and right channel
+    pub(crate) fn channels(self) -> (usize, usize) {
+        match self {
+            MeterSource::Input => (IN_L, IN_R),
+            MeterSource::Output => (OUT_L, OUT_R),
+        }
+    }
+}
+
+/// The range of a meter, from the given dBFS up to 0 dBFS.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum MeterScale {
+    DB24,
+    DB48,
+    DB60,
+    DB96,
+}
+
+impl MeterScale {
+    pub(crate) fn floor_db(self) -> f32 {
+        match self {
+            MeterScale::DB24 => -24.0,
+            MeterScale::DB48 => -48.0,
+            MeterScale::DB60 => -60.0,
+            MeterScale::DB96 => -96.0,
+        }
+    }
+
+    // the position of a level on the scale, from 0.0 to 1.0
+    pub(crate) fn to_normal(self, db: f32) -> f32 {
+        let floor_db = self.floor_db();
+
+        ((db - floor_db) / -floor_db).max(0.0).min(1.0)
+    }
+}
+
+/// The levels of the input and output. The audio thread writes them and the GUI
+/// reads them on each tick without locking or allocating.
+pub(crate) struct Levels {
+    // the bits of the largest absolute sample since the GUI last read it
+    peaks: [AtomicU32; NUM_CHANNELS],
+    // the bits of the smoothed mean square
+    mean_squares: [AtomicU32; NUM_CHANNELS],
+    clips: [AtomicBool; NUM_CHANNELS],
+}
+
+impl Levels {
+    pub(crate) fn new() -> Self {
+        Self {
+            peaks: Default::default(),
+            mean_squares: Default::default(),
+            clips: Default::default(),
+        }
+    }
+
+    /// Measures a block of one channel. `mean_square` is the running mean
+    /// square of the channel, which the audio thread keeps between blocks.
+    pub(crate) fn measure(
+        &self,
+        channel: usize,
+        samples: &[f32],
+        rms_coeff: f32,
+        mean_square: &mut f32,
+    ) {
+        let mut peak = 0.0_f32;
+        let mut non_finite = false;
+        for sample in samples.iter() {
+            // a NaN or infinity is left out of the levels but shows up as a clip
+            if !sample.is_finite() {
+                non_finite = true;
+                continue;
+            }
+
+            peak = peak.max(sample.abs());
+            *mean_square += (sample * sample - *mean_square) * (1.0 - rms_coeff);
+        }
+
+        // e.g. after an overflow, which would otherwise stick forever
+        if !mean_square.is_finite() {
+            *mean_square = 0.0;
+        }
+
+        // the bits of positive floats are ordered like their values
+        self.peaks[channel].fetch_max(peak.to_bits(), Ordering::Relaxed);
+
+        self.mean_squares[channel].store(mean_square.to_bits(), Ordering::Relaxed);
+
+        if peak >= 1.0 || non_finite {
+            self.clips[channel].store(true, Ordering::Relaxed);
+        }
+    }
+
+    fn take_peak(&self, channel: usize) -> f32 {
+        f32::from_bits(self.peaks[channel].swap(0, Ordering::Relaxed))
+    }
+
+    fn mean_square(&self, channel: usize) -> f32 {
+        f32::from_bits(self.mean_squares[channel].load(Ordering::Relaxed))
+    }
+
+    fn take_clip(&self, channel: usize) -> bool {
+        self.clips[channel].swap(false, Ordering::Relaxed)
+    }
+}
+
+/// The displayed level of one channel.
+#[derive(Debug, Copy, Clone)]
+pub(crate) struct ChannelLevel {
+    pub peak_db: f32,
+    pub hold_db: f32,
+    pub rms_db: f32,
+    /// Stays set after the channel clipped until it is reset.
+    pub clipped: bool,
+    hold_secs: f32,
+}
+
+impl Default for ChannelLevel {
+    fn default() -> Self {
+        Self {
+            peak_db: SILENCE_DB,
+            hold_db: SILENCE_DB,
+            rms_db: SILENCE_DB,
+            clipped: false,
+            hold_secs: 0.0,
+        }
+    }
+}
+
+impl ChannelLevel {
+    fn update(&mut self, levels: &Levels, channel: usize, delta_secs: f32) {
+        let peak_db = amp_to_db(levels.take_peak(channel));
+        self.peak_db = peak_db.max(self.peak_db - PEAK_FALL_DB_PER_SEC * delta_secs);
+
+        self.hold_secs -= delta_secs;
+        if self.peak_db >= self.hold_db || self.hold_secs <= 0.0 {
+            self.hold_db = self.peak_db;
+            self.hold_secs = PEAK_HOLD_SECS;
+        }
+
+        self.rms_db = amp_to_db(levels.mean_square(channel).sqrt());
+
+        if levels.take_clip(channel) {
+            self.clipped = true;
+        }
+    }
+}
+
+/// The displayed levels of the input and output, shared by all meters.
+pub(crate) struct Meters {
+    channels: [ChannelLevel; NUM_CHANNELS],
+}
+
+impl Meters {
+    pub(crate) fn new() -> Self {
+        Self {
+            channels: Default::default(),
+        }
+    }
+
+    /// Picks up the levels measured since the last tick.
+    pub(crate) fn update(&mut self, levels: &Levels, delta_secs: f32) {
+        for (channel, level) in self.channels.iter_mut().enumerate() {
+            level.update(levels, channel, delta_secs);
+        }
+    }
+
+    pub(crate) fn channel(&self, channel: usize) -> ChannelLevel {
+        self.channels[channel]
+    }
+
+    pub(crate) fn reset_clips(&mut self, source: MeterSource) {
+        let (left, right) = source.channels();
+        self.channels[left].clipped = false;
+        self.channels[right].clipped = false;
+    }
+}
+
+fn amp_to_db(amp: f32) -> f32 {
+    if amp > 0.0 {
+        (20.0 * amp.log10()).max(SILENCE_DB)
+    } else {
+        SILENCE_DB
+    }
+}
@@ -0,0 +1,165 @@
+use crate::config;
+
+use std::path::PathBuf;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct MidiMapping {
+    pub channel: u8,
+    pub cc: u8,
+    pub param_id: u32,
+}
+
+/// MIDI CC to parameter mappings, stored in a plain text file with one
+/// `<channel> <cc> <param key>` mapping per line.
+pub struct MidiMap {
+    mappings: Vec<MidiMapping>,
+    path: PathBuf,
+    // the key of each parameter, indexed by id
+    keys: Vec<&'static str>,
+}
+
+impl MidiMap {
+    pub fn load(path: PathBuf, keys: Vec<&'static str>) -> Self {
+        let mut mappings: Vec<MidiMapping> = Vec::new();
+
+        if let Ok(contents) = std::fs::read_to_string(&path) {
+            for line in contents.lines() {
+                let line = line.trim();
+                if line.is_empty() || line.starts_with('#') {
+                    continue;
+                }
+
+                match Self::parse_line(line, &keys) {
+                    Some(mapping) => mappings.push(mapping),
+                    None => eprintln!("ignoring invalid MIDI mapping `{}` in {:?}", line, path),
+                }
+            }
+        }
+
+        Self {
+            mappings,
+            path,
+            keys,
+        }
+    }
+
+    fn parse_line(line: &str, keys: &[&'static str]) -> Option<MidiMapping> {
+        let mut fields = line.splitn(3, ' ');
+
+        let channel = fields.next()?.parse::<u8>().ok()?;
+        let cc = fields.next()?.parse::<u8>().ok()?;
+        let key = fields.next()?.trim();
+        let param_id = keys.iter().position(|k| *k == key)? as u32;
+
+        if channel > 15 || cc > 127 {
+            return None;
+        }
+
+        Some(MidiMapping {
+            channel,
+            cc,
+            param_id,
+        })
+    }
+
+    pub fn save(&self) {
+        let mut contents = String::from("# channel cc param_key\n");
+        for mapping in self.mappings.iter() {
+            contents.push_str(&format!(
+                "{} {} {}\n",
+                mapping.channel, mapping.cc, self.keys[mapping.param_id as usize]
+            ));
+        }
+
+        if let Err(e) = config::write_app_file(&self.path, &contents) {
+            eprintln!("error saving MIDI mappings to {:?}: {}", self.path, e);
+        }
+    }
+
+    pub fn mappings(&self) -> &[MidiMapping] {
+        &self.mappings
+    }
+
+    /// Maps the controller to the parameter, replacing any previous mapping of
+    /// either one.
+    pub fn learn(&mut self, channel: u8, cc: u8, param_id: u32) {
+        self.mappings.retain(|mapping| {
+            mapping.param_id != param_id && !(mapping.channel == channel && mapping.cc == cc)
+        });
+
+        self.mappings.push(MidiMapping {
+            channel,
+            cc,
+            param_id,
+        });
+    }
+
+    pub fn unlearn(&mut self, param_id: u32) {
+        self.mappings.retain(|mapping| mapping.param_id != param_id);
+    }
+
+    pub fn remove(&mut self, index: usize) {
+        if index < self.mappings.len() {
+            self.mappings.remove(index);
+        }
+    }
+
+    pub fn param_ids(&self, channel: u8, cc: u8) -> Vec<u32> {
+        self.mappings
+            .iter()
+            .filter(|mapping| mapping.channel == channel && mapping.cc == cc)
+            .map(|mapping| mapping.param_id)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    static KEYS: [&str; 3] = ["gain", "cutoff freq", "mix"];
+
+    #[test]
+    fn save_and_load() {
+        let path = config::temp_path("midimap");
+
+        let mut midi_map = MidiMap::load(path.clone(), KEYS.to_vec());
+        assert!(midi_map.mappings().is_empty());
+        midi_map.learn(0, 7, 0);
+        midi_map.learn(15, 74, 1);
+        midi_map.save();
+
+        let loaded = MidiMap::load(path.clone(), KEYS.to_vec());
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(loaded.mappings(), midi_map.mappings());
+    }
+
+    #[test]
+    fn learn_replaces_mappings_of_the_param_and_the_controller() {
+        let mut midi_map = MidiMap::load(config::temp_path("no_midimap"), KEYS.to_vec());
+        midi_map.learn(0, 7, 0);
+        midi_map.learn(0, 8, 1);
+        midi_map.learn(0, 9, 0);
+        midi_map.learn(0, 8, 2);
+
+        assert_eq!(midi_map.param_ids(0, 7), Vec::<u32>::new());
+        assert_eq!(midi_map.param_ids(0, 8), vec![2]);
+        assert_eq!(midi_map.param_ids(0, 9), vec![0]);
+    }
+
+    #[test]
+    fn invalid_lines_are_rejected() {
+        assert_eq!(
+            MidiMap::parse_line("1 74 cutoff freq", &KEYS),
+            Some(MidiMapping {
+                channel: 1,
+                cc: 74,
+                param_id: 1,
+            })
+        );
+        assert_eq!(MidiMap::parse_line("16 74 gain", &KEYS), None);
+        assert_eq!(MidiMap::parse_line("0 128 gain", &KEYS), None);
+        assert_eq!(MidiMap::parse_line("0 7 volume", &KEYS), None);
+    }
+}
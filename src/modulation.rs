@@ -0,0 +1,319 @@
+use crate::Range;
+
+use iced_audio::Normal;
+
+use std::sync::atomic::{AtomicU32, Ordering};
+
+// how much faster or slower one press of a rate control makes the modulation
+static SPEED_STEP: f32 = 1.25;
+static MIN_RATE_HZ: f32 = 0.01;
+static MAX_RATE_HZ: f32 = 50.0;
+static MAX_TIME_MS: f32 = 5000.0;
+
+// the change of one press of a depth control
+static DEPTH_STEP: f32 = 0.05;
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum LfoShape {
+    Sine,
+    Triangle,
+    Saw,
+    Square,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum ModSource {
+    Lfo {
+        shape: LfoShape,
+        rate_hz: f32,
+    },
+    /// Holds a new random value `rate_hz` times per second.
+    SampleAndHold {
+        rate_hz: f32,
+    },
+    /// Follows the level of the input signal.
+    EnvelopeFollower {
+        attack_ms: f32,
+        release_ms: f32,
+    },
+}
+
+impl ModSource {
+    pub fn name(&self) -> &'static str {
+        match self {
+            ModSource::Lfo { .. } => "LFO",
+            ModSource::SampleAndHold { .. } => "S&H",
+            ModSource::EnvelopeFollower { .. } => "Env",
+        }
+    }
+}
+
+/// A modulation source routed to a parameter. `depth` is in normals, so a
+/// depth of `0.5` with an LFO sweeps half of the knob's range on either side
+/// of the knob's position.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Modulation {
+    pub source: ModSource,
+    pub depth: f32,
+}
+
+impl Modulation {
+    pub fn lfo(shape: LfoShape, rate_hz: f32, depth: f32) -> Self {
+        Self {
+            source: ModSource::Lfo { shape, rate_hz },
+            depth,
+        }
+    }
+
+    pub fn sample_and_hold(rate_hz: f32, depth: f32) -> Self {
+        Self {
+            source: ModSource::SampleAndHold { rate_hz },
+            depth,
+        }
+    }
+
+    pub fn envelope_follower(attack_ms: f32, release_ms: f32, depth: f32) -> Self {
+        Self {
+            source: ModSource::EnvelopeFollower {
+                attack_ms,
+                release_ms,
+            },
+            depth,
+        }
+    }
+
+    /// Returns the next modulation in the order Off -> LFO -> S&H -> Env -> Off.
+    /// `preferred` is used when it is of the next kind, otherwise defaults are used.
+    pub(crate) fn cycle(
+        current: Option<Modulation>,
+        preferred: Option<Modulation>,
+    ) -> Option<Self> {
+        let next = match current.map(|m| m.source) {
+            None => Modulation::lfo(LfoShape::Sine, 1.0, 0.25),
+            Some(ModSource::Lfo { .. }) => Modulation::sample_and_hold(4.0, 0.25),
+            Some(ModSource::SampleAndHold { .. }) => {
+                Modulation::envelope_follower(10.0, 150.0, 0.5)
+            }
+            Some(ModSource::EnvelopeFollower { .. }) => return None,
+        };
+
+        match preferred {
+            Some(preferred)
+                if std::mem::discriminant(&preferred.source)
+                    == std::mem::discriminant(&next.source) =>
+            {
+                Some(preferred)
+            }
+            _ => Some(next),
+        }
+    }
+}
+
+/// A control of a parameter's modulation in its block menu.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ModCtrl {
+    Slower,
+    Faster,
+    LessDepth,
+    MoreDepth,
+}
+
+impl Modulation {
+    /// Changes the rate or the depth by one step.
+    pub(crate) fn adjust(&mut self, ctrl: ModCtrl) {
+        match ctrl {
+            ModCtrl::Slower => self.scale_speed(1.0 / SPEED_STEP),
+            ModCtrl::Faster => self.scale_speed(SPEED_STEP),
+            ModCtrl::LessDepth => self.depth = (self.depth - DEPTH_STEP).max(-1.0),
+            ModCtrl::MoreDepth => self.depth = (self.depth + DEPTH_STEP).min(1.0),
+        }
+    }
+
+    /// The rate of an LFO or S&H, or the release time of an envelope follower.
+    pub(crate) fn speed_text(&self) -> String {
+        match self.source {
+            ModSource::Lfo { rate_hz, .. } | ModSource::SampleAndHold { rate_hz } => {
+                format!("{:.2} Hz", rate_hz)
+            }
+            ModSource::EnvelopeFollower { release_ms, .. } => format!("{:.0} ms", release_ms),
+        }
+    }
+
+    pub(crate) fn depth_text(&self) -> String {
+        format!("{:.0}%", self.depth * 100.0)
+    }
+
+    // a faster envelope follower attacks and releases sooner
+    fn scale_speed(&mut self, factor: f32) {
+        match &mut self.source {
+            ModSource::Lfo { rate_hz, .. } | ModSource::SampleAndHold { rate_hz } => {
+                *rate_hz = (*rate_hz * factor).max(MIN_RATE_HZ).min(MAX_RATE_HZ);
+            }
+            ModSource::EnvelopeFollower {
+                attack_ms,
+                release_ms,
+            } => {
+                *attack_ms = (*attack_ms / factor).min(MAX_TIME_MS);
+                *release_ms = (*release_ms / factor).min(MAX_TIME_MS);
+            }
+        }
+    }
+}
+
+/// The effective normals of the modulated parameters. The audio thread writes
+/// them on each block and the GUI reads them on each tick without locking or
+/// allocating.
+pub(crate) struct ModulatedNormals {
+    // the bits of an `f32`, indexed by parameter id
+    normals: Vec<AtomicU32>,
+}
+
+impl ModulatedNormals {
+    pub(crate) fn new(num_params: usize) -> Self {
+        Self {
+            normals: (0..num_params).map(|_| AtomicU32::new(0)).collect(),
+        }
+    }
+
+    pub(crate) fn set(&self, id: usize, normal: f32) {
+        if let Some(stored) = self.normals.get(id) {
+            stored.store(normal.to_bits(), Ordering::Relaxed);
+        }
+    }
+
+    pub(crate) fn get(&self, id: usize) -> f32 {
+        self.normals
+            .get(id)
+            .map_or(0.0, |normal| f32::from_bits(normal.load(Ordering::Relaxed)))
+    }
+}
+
+/// A modulation route as it runs on the audio thread. It owns no heap data,
+/// so replacing one there frees nothing.
+pub(crate) struct Modulator {
+    modulation: Modulation,
+    range: Range,
+    phase: f32,
+    held: f32,
+    envelope: f32,
+    rng_state: u32,
+}
+
+impl Modulator {
+    pub fn new(modulation: Modulation, range: Range) -> Self {
+        Self {
+            modulation,
+            range,
+            phase: 0.0,
+            held: 0.0,
+            envelope: 0.0,
+            rng_state: 0x9E37_79B9,
+        }
+    }
+
+    /// Advances the modulator by one block and returns the effective normal
+    /// of a parameter with the given base value.
+    pub fn process(
+        &mut self,
+        base_value: f32,
+        in_l: &[f32],
+        in_r: &[f32],
+        sample_rate: f32,
+    ) -> Normal {
+        let num_frames = in_l.len();
+
+        let amount = match self.modulation.source {
+            ModSource::Lfo { shape, rate_hz } => {
+                self.advance_phase(rate_hz, num_frames, sample_rate);
+
+                match shape {
+                    LfoShape::Sine => (self.phase * std::f32::consts::PI * 2.0).sin(),
+                    LfoShape::Triangle => 1.0 - 4.0 * (self.phase - 0.5).abs(),
+                    LfoShape::Saw => 2.0 * self.phase - 1.0,
+                    LfoShape::Square => {
+                        if self.phase < 0.5 {
+                            1.0
+                        } else {
+                            -1.0
+                        }
+                    }
+                }
+            }
+            ModSource::SampleAndHold { rate_hz } => {
+                if self.advance_phase(rate_hz, num_frames, sample_rate) {
+                    self.held = self.next_random();
+                }
+
+                self.held
+            }
+            ModSource::EnvelopeFollower {
+                attack_ms,
+                release_ms,
+            } => {
+                let attack_coeff = Self::one_pole_coeff(attack_ms, sample_rate);
+                let release_coeff = Self::one_pole_coeff(release_ms, sample_rate);
+
+                for (l, r) in in_l.iter().zip(in_r.iter()) {
+                    let level = l.abs().max(r.abs());
+                    let coeff = if level > self.envelope {
+                        attack_coeff
+                    } else {
+                        release_coeff
+                    };
+                    self.envelope = level + coeff * (self.envelope - level);
+                }
+
+                self.envelope.min(1.0)
+            }
+        };
+
+        let base_normal = self.range.to_normal(base_value).value();
+        let mut normal = Normal::new(
+            (base_normal + amount * self.modulation.depth)
+                .max(0.0)
+                .min(1.0),
+        );
+        self.range.snap_normal(&mut normal);
+
+        normal
+    }
+
+    /// Changes the settings of the route without restarting it.
+    pub fn set_modulation(&mut self, modulation: Modulation, range: Range) {
+        self.modulation = modulation;
+        self.range = range;
+    }
+
+    pub fn range(&self) -> &Range {
+        &self.range
+    }
+
+    // returns true if the phase wrapped around
+    fn advance_phase(&mut self, rate_hz: f32, num_frames: usize, sample_rate: f32) -> bool {
+        self.phase += rate_hz * num_frames as f32 / sample_rate;
+
+        if self.phase >= 1.0 {
+            self.phase = self.phase.fract();
+            true
+        } else {
+            false
+        }
+    }
+
+    fn one_pole_coeff(time_ms: f32, sample_rate: f32) -> f32 {
+        if time_ms <= 0.0 {
+            0.0
+        } else {
+            (-1.0 / (time_ms * 0.001 * sample_rate)).exp()
+        }
+    }
+
+    // xorshift, so no allocation or locking happens on the audio thread
+    fn next_random(&mut self) -> f32 {
+        self.rng_state ^= self.rng_state << 13;
+        self.rng_state ^= self.rng_state >> 17;
+        self.rng_state ^= self.rng_state << 5;
+
+        (self.rng_state as f32 / std::u32::MAX as f32) * 2.0 - 1.0
+    }
+}
@@ -0,0 +1,213 @@
+use crate::Param;
+
+use rosc::{OscMessage, OscPacket, OscType};
+
+use std::net::{SocketAddr, UdpSocket};
+use std::sync::mpsc;
+use std::time::Duration;
+
+// the server stops after this many receive errors in a row
+static MAX_RECV_ERRORS: u32 = 10;
+
+/// Commands received by the OSC server, applied by the GUI on each tick.
+pub enum OscCommand {
+    SetParam(Param),
+    Play,
+    Pause,
+    Stop,
+    Bypass(bool),
+    Panic,
+}
+
+/// A parameter as it is described to OSC clients.
+pub struct OscParam {
+    pub id: u32,
+    pub key: String,
+    pub min: f32,
+    pub max: f32,
+    pub default: f32,
+}
+
+/// Starts a thread that receives OSC messages on the given address:
+///
+/// ```text
+/// /param/<key> <value>     sets a parameter, spaces in the key may be written as `_`
+/// /param/<key>/range       replies with `/param/<key>/range <min> <max> <default>`
+/// /params                  replies with `/params <key> <key> ...`
+/// /transport/play
+/// /transport/pause
+/// /transport/stop
+/// /bypass <0 or 1>
+/// /panic
+/// ```
+pub fn spawn(addr: SocketAddr, params: Vec<OscParam>) -> Option<mpsc::Receiver<OscCommand>> {
+    let socket = match UdpSocket::bind(addr) {
+        Ok(socket) => socket,
+        Err(e) => {
+            eprintln!("error starting OSC server on {}: {}", addr, e);
+            return None;
+        }
+    };
+    println!("OSC server listening on {}", addr);
+
+    let (tx, rx) = mpsc::channel::<OscCommand>();
+
+    std::thread::spawn(move || {
+        let mut buf = [0u8; rosc::decoder::MTU];
+        let mut recv_errors = 0;
+
+        loop {
+            let (size, from) = match socket.recv_from(&mut buf) {
+                Ok(received) => {
+                    recv_errors = 0;
+                    received
+                }
+                Err(e) => {
+                    recv_errors += 1;
+                    if recv_errors >= MAX_RECV_ERRORS {
+                        eprintln!("stopping OSC server after repeated errors: {}", e);
+                        break;
+                    }
+
+                    eprintln!("error receiving OSC message: {}", e);
+                    // waits longer after each error so a persistent one doesn't
+                    // flood stderr or keep a core busy
+                    std::thread::sleep(Duration::from_millis(10 << recv_errors));
+                    continue;
+                }
+            };
+
+            let packet = match rosc::decoder::decode(&buf[..size]) {
+                Ok(packet) => packet,
+                Err(e) => {
+                    eprintln!("ignoring invalid OSC packet: {:?}", e);
+                    continue;
+                }
+            };
+
+            let server = Server {
+                socket: &socket,
+                from,
+                params: &params,
+                tx: &tx,
+            };
+            if server.handle_packet(packet).is_err() {
+                // the GUI has been closed
+                break;
+            }
+        }
+    });
+
+    Some(rx)
+}
+
+struct Server<'a> {
+    socket: &'a UdpSocket,
+    from: SocketAddr,
+    params: &'a [OscParam],
+    tx: &'a mpsc::Sender<OscCommand>,
+}
+
+impl<'a> Server<'a> {
+    fn handle_packet(&self, packet: OscPacket) -> Result<(), mpsc::SendError<OscCommand>> {
+        match packet {
+            OscPacket::Message(msg) => self.handle_message(msg),
+            OscPacket::Bundle(bundle) => {
+                for packet in bundle.content {
+                    self.handle_packet(packet)?;
+                }
+                Ok(())
+            }
+        }
+    }
+
+    fn handle_message(&self, msg: OscMessage) -> Result<(), mpsc::SendError<OscCommand>> {
+        let arg = msg.args.first().and_then(arg_to_f32);
+
+        match msg.addr.as_str() {
+            "/params" => {
+                let keys = self
+                    .params
+                    .iter()
+                    .map(|param| OscType::String(param.key.clone()))
+                    .collect();
+                self.reply("/params", keys);
+            }
+            "/transport/play" => self.tx.send(OscCommand::Play)?,
+            "/transport/pause" => self.tx.send(OscCommand::Pause)?,
+            "/transport/stop" => self.tx.send(OscCommand::Stop)?,
+            "/bypass" => self.tx.send(OscCommand::Bypass(
+                arg.map(|arg| arg != 0.0).unwrap_or(true),
+            ))?,
+            "/panic" => self.tx.send(OscCommand::Panic)?,
+            addr if addr.starts_with("/param/") => {
+                let key = &addr["/param/".len()..];
+
+                if key.ends_with("/range") {
+                    let key = &key[..key.len() - "/range".len()];
+                    match self.find_param(key) {
+                        Some(param) => self.reply(
+                            addr,
+                            vec![
+                                OscType::Float(param.min),
+                                OscType::Float(param.max),
+                                OscType::Float(param.default),
+                            ],
+                        ),
+                        None => eprintln!("OSC: unknown parameter `{}`", key),
+                    }
+                } else {
+                    match (self.find_param(key), arg) {
+                        (Some(_), Some(value)) if value.is_nan() => {
+                            eprintln!("OSC: {} needs a number", addr)
+                        }
+                        (Some(param), Some(value)) => {
+                            self.tx.send(OscCommand::SetParam(Param {
+                                id: param.id,
+                                value: value.max(param.min).min(param.max),
+                            }))?
+                        }
+                        (None, _) => eprintln!("OSC: unknown parameter `{}`", key),
+                        (_, None) => eprintln!("OSC: {} needs a number", addr),
+                    }
+                }
+            }
+            addr => eprintln!("OSC: unknown address {}", addr),
+        }
+
+        Ok(())
+    }
+
+    fn find_param(&self, key: &str) -> Option<&OscParam> {
+        self.params
+            .iter()
+            .find(|param| param.key == key || param.key.replace(' ', "_") == key)
+    }
+
+    fn reply(&self, addr: &str, args: Vec<OscType>) {
+        let packet = OscPacket::Message(OscMessage {
+            addr: String::from(addr),
+            args,
+        });
+
+        match rosc::encoder::encode(&packet) {
+            Ok(bytes) => {
+                if let Err(e) = self.socket.send_to(&bytes, self.from) {
+                    eprintln!("error sending OSC reply to {}: {}", self.from, e);
+                }
+            }
+            Err(e) => eprintln!("error encoding OSC reply: {:?}", e),
+        }
+    }
+}
+
+fn arg_to_f32(arg: &OscType) -> Option<f32> {
+    match arg {
+        OscType::Float(value) => Some(*value),
+        OscType::Double(value) => Some(*value as f32),
+        OscType::Int(value) => Some(*value as f32),
+        OscType::Long(value) => Some(*value as f32),
+        OscType::Bool(value) => Some(if *value { 1.0 } else { 0.0 }),
+        _ => None,
+    }
+}
@@ -0,0 +1,38 @@
+use std::sync::atomic::{AtomicU32, Ordering};
+
+/// Named scalar values published by the DSP, such as gain reduction or a
+/// detected pitch. The audio thread writes them and the GUI reads them on each
+/// tick without locking or allocating.
+pub struct OutputValues {
+    names: Vec<&'static str>,
+    // the bits of an `f32`
+    values: Vec<AtomicU32>,
+}
+
+impl OutputValues {
+    pub(crate) fn new(names: Vec<&'static str>) -> Self {
+        let values = names.iter().map(|_| AtomicU32::new(0)).collect();
+
+        Self { names, values }
+    }
+
+    /// Publishes a value. Values with a name that no block displays are ignored.
+    pub fn publish(&self, name: &str, value: f32) {
+        if let Some(index) = self.index(name) {
+            self.values[index].store(value.to_bits(), Ordering::Relaxed);
+        }
+    }
+
+    /// Returns the last published value with the given name.
+    pub fn get(&self, name: &str) -> Option<f32> {
+        self.index(name).map(|index| self.value(index))
+    }
+
+    pub(crate) fn index(&self, name: &str) -> Option<usize> {
+        self.names.iter().position(|n| *n == name)
+    }
+
+    pub(crate) fn value(&self, index: usize) -> f32 {
+        f32::from_bits(self.values[index].load(Ordering::Relaxed))
+    }
+}
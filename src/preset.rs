@@ -0,0 +1,151 @@
+use crate::automation::{AutomationLane, AutomationPoint};
+use crate::config;
+
+use std::path::PathBuf;
+
+/// Parameter values and automation lanes by parameter key, stored in a plain
+/// text file:
+///
+/// ```text
+/// param <key> = <value>
+/// lane <key> = <frame>:<value> <frame>:<value> ...
+/// ```
+pub struct Preset {
+    pub params: Vec<(String, f32)>,
+    pub lanes: Vec<(String, AutomationLane)>,
+}
+
+impl Preset {
+    pub fn load(path: &PathBuf) -> Result<Self, String> {
+        let contents = std::fs::read_to_string(path)
+            .map_err(|e| format!("error reading preset {:?}: {}", path, e))?;
+
+        let mut preset = Self {
+            params: Vec::new(),
+            lanes: Vec::new(),
+        };
+
+        for line in contents.lines() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            if preset.parse_line(line).is_none() {
+                eprintln!("ignoring invalid line `{}` in preset {:?}", line, path);
+            }
+        }
+
+        Ok(preset)
+    }
+
+    fn parse_line(&mut self, line: &str) -> Option<()> {
+        let mut halves = line.splitn(2, '=');
+        let mut head = halves.next()?.trim().splitn(2, ' ');
+        let kind = head.next()?;
+        let key = String::from(head.next()?.trim());
+        let data = halves.next()?;
+
+        match kind {
+            "param" => {
+                let value = data.trim().parse::<f32>().ok()?;
+
+                self.params.push((key, value));
+            }
+            "lane" => {
+                let mut points: Vec<AutomationPoint> = Vec::new();
+                for field in data.split_whitespace() {
+                    let mut point = field.split(':');
+                    let frame = point.next()?.parse::<usize>().ok()?;
+                    let value = point.next()?.parse::<f32>().ok()?;
+
+                    points.push(AutomationPoint { frame, value });
+                }
+
+                self.lanes.push((key, AutomationLane::from_points(points)));
+            }
+            _ => return None,
+        }
+
+        Some(())
+    }
+
+    pub fn save(&self, path: &PathBuf) -> Result<(), String> {
+        let mut contents = String::from("# DSP Box preset\n");
+
+        for (key, value) in self.params.iter() {
+            contents.push_str(&format!("param {} = {}\n", key, value));
+        }
+
+        for (key, lane) in self.lanes.iter() {
+            if lane.is_empty() {
+                continue;
+            }
+
+            contents.push_str(&format!("lane {} =", key));
+            for point in lane.points() {
+                contents.push_str(&format!(" {}:{}", point.frame, point.value));
+            }
+            contents.push('\n');
+        }
+
+        config::write_app_file(path, &contents)
+            .map_err(|e| format!("error saving preset {:?}: {}", path, e))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn save_and_load() {
+        let preset = Preset {
+            params: vec![
+                (String::from("gain"), -3.5),
+                (String::from("cutoff freq"), 1200.0),
+            ],
+            lanes: vec![
+                (
+                    String::from("gain"),
+                    AutomationLane::from_points(vec![
+                        AutomationPoint {
+                            frame: 0,
+                            value: -12.0,
+                        },
+                        AutomationPoint {
+                            frame: 48000,
+                            value: 0.25,
+                        },
+                    ]),
+                ),
+                (String::from("cutoff freq"), AutomationLane::new()),
+            ],
+        };
+
+        let path = config::temp_path("preset.dspbox");
+        preset.save(&path).unwrap();
+        let loaded = Preset::load(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(loaded.params, preset.params);
+        // empty lanes are not saved
+        assert_eq!(loaded.lanes.len(), 1);
+        assert_eq!(loaded.lanes[0].0, "gain");
+        assert_eq!(loaded.lanes[0].1.points(), preset.lanes[0].1.points());
+    }
+
+    #[test]
+    fn invalid_lines_are_rejected() {
+        let mut preset = Preset {
+            params: Vec::new(),
+            lanes: Vec::new(),
+        };
+
+        assert!(preset.parse_line("param gain = loud").is_none());
+        assert!(preset.parse_line("lane gain = 0:1.0 100").is_none());
+        assert!(preset.parse_line("knob gain = 1.0").is_none());
+        assert!(preset.params.is_empty());
+        assert!(preset.lanes.is_empty());
+    }
+}
@@ -0,0 +1,231 @@
+use iced_audio::{DBRange, FloatRange, FreqRange, IntRange, Normal};
+
+#[derive(Copy, Clone)]
+pub enum Range {
+    Float(FloatRange),
+    Int(IntRange),
+    DB(DBRange),
+    Freq(FreqRange),
+    /// A range with a skewed (exponential) response.
+    Skewed(SkewRange),
+    /// A linear range from `-max` to `max` centered on zero.
+    Bipolar(FloatRange),
+    /// An on/off range with the values `0.0` and `1.0`.
+    Bool,
+    /// A time in milliseconds, displayed in ms or s.
+    Time(SkewRange),
+    /// A linear range in percent.
+    Percent(FloatRange),
+    /// A pitch offset in whole semitones.
+    Semitones(IntRange),
+    /// A pitch offset in cents.
+    Cents(FloatRange),
+}
+
+impl Range {
+    pub fn float(min: f32, max: f32) -> Self {
+        Range::Float(FloatRange::new(min, max))
+    }
+
+    pub fn int(min: i32, max: i32) -> Self {
+        Range::Int(IntRange::new(min, max))
+    }
+
+    pub fn db(min: f32, max: f32, zero_db_pos: ZeroDBPos) -> Self {
+        let zero_pos = match zero_db_pos {
+            ZeroDBPos::Center => iced_audio::Normal::center(),
+            ZeroDBPos::Min => iced_audio::Normal::min(),
+            ZeroDBPos::Max => iced_audio::Normal::max(),
+            ZeroDBPos::ThreeFourths => iced_audio::Normal::new(0.75),
+        };
+        Range::DB(DBRange::new(min, max, zero_pos))
+    }
+
+    pub fn freq(min: f32, max: f32) -> Self {
+        Range::Freq(FreqRange::new(min, max))
+    }
+
+    /// A skew below `1.0` gives more resolution to the low end of the range,
+    /// and a skew above `1.0` gives more resolution to the high end.
+    pub fn skewed(min: f32, max: f32, skew: f32) -> Self {
+        Range::Skewed(SkewRange::new(min, max, skew))
+    }
+
+    /// A skewed range where `center` sits in the middle of the knob.
+    pub fn skewed_with_center(min: f32, max: f32, center: f32) -> Self {
+        Range::Skewed(SkewRange::with_center(min, max, center))
+    }
+
+    pub fn bipolar(max: f32) -> Self {
+        Range::Bipolar(FloatRange::new(-max.abs(), max.abs()))
+    }
+
+    pub fn boolean() -> Self {
+        Range::Bool
+    }
+
+    /// A time range in milliseconds with more resolution for short times.
+    pub fn time(min_ms: f32, max_ms: f32) -> Self {
+        Range::Time(SkewRange::new(min_ms, max_ms, 0.3))
+    }
+
+    pub fn percent(min: f32, max: f32) -> Self {
+        Range::Percent(FloatRange::new(min, max))
+    }
+
+    pub fn semitones(min: i32, max: i32) -> Self {
+        Range::Semitones(IntRange::new(min, max))
+    }
+
+    pub fn cents(min: f32, max: f32) -> Self {
+        Range::Cents(FloatRange::new(min, max))
+    }
+
+    pub(crate) fn to_normal(&self, value: f32) -> Normal {
+        match self {
+            Range::Float(float_range) => float_range.to_normal(value),
+            Range::Int(int_range) => int_range.to_normal(value.round() as i32),
+            Range::DB(db_range) => db_range.to_normal(value),
+            Range::Freq(freq_range) => freq_range.to_normal(value),
+            Range::Skewed(skew_range) => skew_range.to_normal(value),
+            Range::Bipolar(float_range) => float_range.to_normal(value),
+            Range::Bool => {
+                if value >= 0.5 {
+                    Normal::max()
+                } else {
+                    Normal::min()
+                }
+            }
+            Range::Time(skew_range) => skew_range.to_normal(value),
+            Range::Percent(float_range) => float_range.to_normal(value),
+            Range::Semitones(int_range) => int_range.to_normal(value.round() as i32),
+            Range::Cents(float_range) => float_range.to_normal(value),
+        }
+    }
+
+    pub(crate) fn to_value(&self, normal: Normal) -> f32 {
+        match self {
+            Range::Float(float_range) => float_range.to_value(normal),
+            Range::Int(int_range) => int_range.to_value(normal) as f32,
+            Range::DB(db_range) => db_range.to_value(normal),
+            Range::Freq(freq_range) => freq_range.to_value(normal),
+            Range::Skewed(skew_range) => skew_range.to_value(normal),
+            Range::Bipolar(float_range) => float_range.to_value(normal),
+            Range::Bool => {
+                if normal.value() >= 0.5 {
+                    1.0
+                } else {
+                    0.0
+                }
+            }
+            Range::Time(skew_range) => skew_range.to_value(normal),
+            Range::Percent(float_range) => float_range.to_value(normal),
+            Range::Semitones(int_range) => int_range.to_value(normal) as f32,
+            Range::Cents(float_range) => float_range.to_value(normal),
+        }
+    }
+
+    /// Snaps the normal to the nearest valid step for stepped ranges.
+    pub(crate) fn snap_normal(&self, normal: &mut Normal) {
+        match self {
+            Range::Int(int_range) | Range::Semitones(int_range) => int_range.snap_normal(normal),
+            Range::Bool => *normal = self.to_normal(self.to_value(*normal)),
+            _ => {}
+        }
+    }
+}
+
+impl Default for Range {
+    fn default() -> Self {
+        Range::float(0.0, 1.0)
+    }
+}
+
+pub enum ZeroDBPos {
+    Center,
+    Min,
+    Max,
+    ThreeFourths,
+}
+
+impl Default for ZeroDBPos {
+    fn default() -> Self {
+        ZeroDBPos::Center
+    }
+}
+
+/// A range that maps normals to values with an exponential skew factor.
+#[derive(Debug, Copy, Clone)]
+pub struct SkewRange {
+    min: f32,
+    max: f32,
+    skew: f32,
+}
+
+impl SkewRange {
+    pub fn new(min: f32, max: f32, skew: f32) -> Self {
+        assert!(max > min, "max must be greater than min");
+        assert!(skew > 0.0, "skew must be greater than 0");
+
+        Self { min, max, skew }
+    }
+
+    pub fn with_center(min: f32, max: f32, center: f32) -> Self {
+        assert!(
+            center > min && center < max,
+            "center must be between min and max"
+        );
+
+        let skew = 0.5f32.ln() / ((center - min) / (max - min)).ln();
+
+        Self::new(min, max, skew)
+    }
+
+    pub fn to_normal(&self, value: f32) -> Normal {
+        let proportion = ((value - self.min) / (self.max - self.min))
+            .max(0.0)
+            .min(1.0);
+
+        Normal::new(proportion.powf(self.skew))
+    }
+
+    pub fn to_value(&self, normal: Normal) -> f32 {
+        self.min + (self.max - self.min) * normal.value().powf(1.0 / self.skew)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn skew_range_round_trip() {
+        let range = SkewRange::new(1.0, 5000.0, 0.3);
+
+        for value in [1.0, 2.5, 100.0, 1234.5, 5000.0].iter() {
+            let round_trip = range.to_value(range.to_normal(*value));
+            assert!((round_trip - value).abs() < value * 1.0e-4);
+        }
+    }
+
+    #[test]
+    fn skew_range_clamps_and_centers() {
+        let range = SkewRange::with_center(20.0, 20_000.0, 1000.0);
+
+        assert!((range.to_normal(1000.0).value() - 0.5).abs() < 1.0e-4);
+        assert_eq!(range.to_normal(0.0).value(), 0.0);
+        assert_eq!(range.to_normal(30_000.0).value(), 1.0);
+    }
+
+    #[test]
+    fn stepped_ranges_snap() {
+        let mut normal = Normal::new(0.3);
+        Range::Bool.snap_normal(&mut normal);
+        assert_eq!(normal.value(), 0.0);
+
+        let range = Range::semitones(-12, 12);
+        let mut normal = range.to_normal(3.4);
+        range.snap_normal(&mut normal);
+        assert_eq!(range.to_value(normal), 3.0);
+    }
+}
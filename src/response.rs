@@ -0,0 +1,88 @@
+use crate::audio_thread;
+use crate::DSPBoxApp;
+
+use rustfft::num_complex::Complex;
+use rustfft::num_traits::Zero;
+use rustfft::FFTplanner;
+
+use std::sync::{mpsc, Arc};
+use std::thread;
+
+// the length of the measured impulse response
+static IMPULSE_LEN: usize = 8192;
+// the impulse response is zero-padded to this length, which gives bins close
+// enough together to be interpolated at the bottom of the log frequency axis
+static FFT_LEN: usize = IMPULSE_LEN * 4;
+
+pub(crate) type AppFactory = Arc<dyn Fn() -> Box<dyn DSPBoxApp + Send> + Send + Sync>;
+
+/// Where the frequency response of a `FrequencyResponse` plot comes from.
+pub enum ResponseSource {
+    /// The complex response at a frequency in Hz given the parameter values,
+    /// indexed like `AudioProcessInfo::in_params`.
+    Analytic(Box<dyn Fn(f32, &[f32]) -> Complex<f32>>),
+    /// Creates a separate instance of the app, e.g.
+    /// `Box::new(|| Box::new(MyFilter::new()))`. It is run on an impulse in a
+    /// background thread whenever a parameter changes, and only its left output
+    /// is measured. Each measurement uses a fresh instance from the factory with
+    /// the current parameter values, not a copy of the running app, so state the
+    /// app keeps apart from its parameters is not reflected.
+    Measured(Box<dyn Fn() -> Box<dyn DSPBoxApp + Send> + Send + Sync>),
+}
+
+/// A response measured in the background, from DC up to Nyquist.
+pub(crate) struct Measurement {
+    bins: Vec<Complex<f32>>,
+    sample_rate: f32,
+}
+
+impl Measurement {
+    /// The response at the frequency, interpolated between the two closest bins.
+    pub(crate) fn at(&self, freq: f32) -> Complex<f32> {
+        let pos = (freq * FFT_LEN as f32 / self.sample_rate).max(0.0);
+        let last = self.bins.len() - 1;
+        let bin = (pos as usize).min(last);
+        let frac = (pos - bin as f32).min(1.0);
+
+        self.bins[bin] * (1.0 - frac) + self.bins[(bin + 1).min(last)] * frac
+    }
+}
+
+/// Runs a new instance of the app on an impulse in a background thread. The
+/// measurement is sent once it is done. The sender is dropped without sending
+/// if the app panicked.
+pub(crate) fn measure(
+    factory: AppFactory,
+    params: Vec<f32>,
+    sample_rate: f32,
+) -> mpsc::Receiver<Measurement> {
+    let (tx, rx) = mpsc::channel();
+
+    thread::spawn(move || {
+        let mut dsp_app = factory();
+
+        let mut impulse = vec![0.0; IMPULSE_LEN];
+        impulse[0] = 1.0;
+        let (out_l, _) =
+            audio_thread::process_offline(&mut *dsp_app, &impulse, &impulse, &params, sample_rate);
+
+        let mut input: Vec<Complex<f32>> = out_l
+            .iter()
+            .map(|sample| Complex::new(*sample, 0.0))
+            .collect();
+        input.resize(FFT_LEN, Complex::zero());
+        let mut output = vec![Complex::zero(); FFT_LEN];
+        FFTplanner::new(false)
+            .plan_fft(FFT_LEN)
+            .process(&mut input, &mut output);
+        output.truncate(FFT_LEN / 2 + 1);
+
+        // the block may have stopped waiting for this measurement
+        let _ = tx.send(Measurement {
+            bins: output,
+            sample_rate,
+        });
+    });
+
+    rx
+}
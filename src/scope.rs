@@ -0,0 +1,78 @@
+use std::sync::atomic::{AtomicU32, AtomicUsize, Ordering};
+
+// the number of samples kept of each signal, enough for the longest scope window
+// at 192kHz
+static CAPACITY: usize = 1 << 18;
+
+/// The recent input and output of the app, summed to mono, for the scopes and
+/// spectrum analyzers. The audio thread only writes it and the GUI reads it
+/// without locking.
+pub(crate) struct ScopeBuffer {
+    // the bits of the `f32` samples
+    input: Vec<AtomicU32>,
+    output: Vec<AtomicU32>,
+    // the number of samples written so far
+    write_pos: AtomicUsize,
+    sample_rate: AtomicU32,
+}
+
+impl ScopeBuffer {
+    /// Nothing is captured when `enabled` is false, i.e. no block needs it.
+    pub(crate) fn new(enabled: bool) -> Self {
+        let capacity = if enabled { CAPACITY } else { 0 };
+
+        Self {
+            input: (0..capacity).map(|_| AtomicU32::new(0)).collect(),
+            output: (0..capacity).map(|_| AtomicU32::new(0)).collect(),
+            write_pos: AtomicUsize::new(0),
+            sample_rate: AtomicU32::new(0),
+        }
+    }
+
+    pub(crate) fn set_sample_rate(&self, sample_rate: f32) {
+        self.sample_rate
+            .store(sample_rate.to_bits(), Ordering::Relaxed);
+    }
+
+    pub(crate) fn sample_rate(&self) -> f32 {
+        f32::from_bits(self.sample_rate.load(Ordering::Relaxed))
+    }
+
+    pub(crate) fn write(&self, in_l: &[f32], in_r: &[f32], out_l: &[f32], out_r: &[f32]) {
+        if self.input.is_empty() {
+            return;
+        }
+
+        let mut pos = self.write_pos.load(Ordering::Relaxed);
+        for i in 0..out_l.len() {
+            let index = pos % self.input.len();
+            let input = (in_l[i] + in_r[i]) * 0.5;
+            let output = (out_l[i] + out_r[i]) * 0.5;
+
+            self.input[index].store(input.to_bits(), Ordering::Relaxed);
+            self.output[index].store(output.to_bits(), Ordering::Relaxed);
+
+            pos += 1;
+        }
+
+        self.write_pos.store(pos, Ordering::Release);
+    }
+
+    /// Copies the latest `len` samples of the input and output, oldest first.
+    /// Fewer are copied if fewer have been written.
+    pub(crate) fn read(&self, len: usize, input: &mut Vec<f32>, output: &mut Vec<f32>) {
+        input.clear();
+        output.clear();
+
+        let end = self.write_pos.load(Ordering::Acquire);
+        let len = len.min(self.input.len()).min(end);
+
+        // the audio thread may overwrite the oldest samples while they are
+        // copied, which only shows as a glitch at the left edge
+        for pos in (end - len)..end {
+            let index = pos % self.input.len();
+            input.push(f32::from_bits(self.input[index].load(Ordering::Relaxed)));
+            output.push(f32::from_bits(self.output[index].load(Ordering::Relaxed)));
+        }
+    }
+}
@@ -0,0 +1,118 @@
+use crate::config;
+
+use std::path::PathBuf;
+
+/// The state of an app that is restored on its next launch, stored in a plain
+/// text file:
+///
+/// ```text
+/// audio_file = <path>
+/// audio_file_gain = <gain>
+/// bypassed = <true or false>
+/// playing = <true or false>
+/// param <key> = <value>
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Session {
+    pub params: Vec<(String, f32)>,
+    pub audio_file_path: Option<String>,
+    pub audio_file_gain: f32,
+    pub bypassed: bool,
+    pub playing: bool,
+}
+
+impl Session {
+    pub fn load(path: &PathBuf) -> Option<Self> {
+        let contents = std::fs::read_to_string(path).ok()?;
+
+        let mut session = Self {
+            params: Vec::new(),
+            audio_file_path: None,
+            audio_file_gain: 1.0,
+            bypassed: false,
+            playing: false,
+        };
+
+        for line in contents.lines() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            if session.parse_line(line).is_none() {
+                eprintln!("ignoring invalid line `{}` in session {:?}", line, path);
+            }
+        }
+
+        Some(session)
+    }
+
+    fn parse_line(&mut self, line: &str) -> Option<()> {
+        let mut halves = line.splitn(2, '=');
+        let head = halves.next()?.trim();
+        let value = halves.next()?.trim();
+
+        match head {
+            "audio_file" => self.audio_file_path = Some(String::from(value)),
+            "audio_file_gain" => self.audio_file_gain = value.parse::<f32>().ok()?,
+            "bypassed" => self.bypassed = value.parse::<bool>().ok()?,
+            "playing" => self.playing = value.parse::<bool>().ok()?,
+            head if head.starts_with("param ") => {
+                let key = head["param ".len()..].trim();
+                let value = value.parse::<f32>().ok()?;
+
+                self.params.push((String::from(key), value));
+            }
+            _ => return None,
+        }
+
+        Some(())
+    }
+
+    pub fn save(&self, path: &PathBuf) {
+        let mut contents = String::from("# DSP Box session\n");
+
+        if let Some(audio_file_path) = &self.audio_file_path {
+            contents.push_str(&format!("audio_file = {}\n", audio_file_path));
+        }
+        contents.push_str(&format!("audio_file_gain = {}\n", self.audio_file_gain));
+        contents.push_str(&format!("bypassed = {}\n", self.bypassed));
+        contents.push_str(&format!("playing = {}\n", self.playing));
+
+        for (key, value) in self.params.iter() {
+            contents.push_str(&format!("param {} = {}\n", key, value));
+        }
+
+        if let Err(e) = config::write_app_file(path, &contents) {
+            eprintln!("error saving session to {:?}: {}", path, e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn save_and_load() {
+        let session = Session {
+            params: vec![(String::from("gain"), -3.5), (String::from("mix"), 0.25)],
+            audio_file_path: Some(String::from("/home/me/audio files/drums.wav")),
+            audio_file_gain: 0.5,
+            bypassed: true,
+            playing: false,
+        };
+
+        let path = config::temp_path("session");
+        session.save(&path);
+        let loaded = Session::load(&path);
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(loaded, Some(session));
+    }
+
+    #[test]
+    fn missing_file_is_none() {
+        assert_eq!(Session::load(&config::temp_path("no_session")), None);
+    }
+}
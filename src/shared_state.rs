@@ -0,0 +1,45 @@
+use crate::audio_thread::{self, Retired};
+use crate::message_ring::MessageRing;
+use crate::meters::Levels;
+use crate::modulation::ModulatedNormals;
+use crate::output_values::OutputValues;
+use crate::scope::ScopeBuffer;
+use crate::GuiSetup;
+
+use std::sync::atomic::AtomicUsize;
+use std::sync::{mpsc, Arc};
+
+/// The state shared between the audio thread and the GUI. The audio thread
+/// writes all of it without locking or allocating.
+#[derive(Clone)]
+pub(crate) struct SharedState {
+    pub audio_to_gui: Arc<MessageRing>,
+    pub transport_pos: Arc<AtomicUsize>,
+    pub output_values: Arc<OutputValues>,
+    pub levels: Arc<Levels>,
+    pub scope: Arc<ScopeBuffer>,
+    pub modulated_normals: Arc<ModulatedNormals>,
+    pub retired_tx: mpsc::SyncSender<Retired>,
+}
+
+impl SharedState {
+    /// Also returns the receiver of the memory the audio thread retires, which
+    /// only the GUI may hold.
+    pub(crate) fn new(gui_setup: &GuiSetup) -> (Self, mpsc::Receiver<Retired>) {
+        let (retired_tx, retired_rx) = mpsc::sync_channel(audio_thread::RETIRED_CAPACITY);
+
+        let shared = Self {
+            audio_to_gui: Arc::new(MessageRing::new()),
+            transport_pos: Arc::new(AtomicUsize::new(0)),
+            output_values: Arc::new(OutputValues::new(gui_setup.output_names.clone())),
+            levels: Arc::new(Levels::new()),
+            scope: Arc::new(ScopeBuffer::new(
+                gui_setup.num_scopes > 0 || gui_setup.num_spectrums > 0,
+            )),
+            modulated_normals: Arc::new(ModulatedNormals::new(gui_setup.next_id as usize)),
+            retired_tx,
+        };
+
+        (shared, retired_rx)
+    }
+}
@@ -0,0 +1,172 @@
+use rustfft::num_complex::Complex;
+use rustfft::num_traits::Zero;
+use rustfft::{FFTplanner, FFT};
+
+use std::f32::consts::PI;
+use std::sync::Arc;
+
+// how much of the previous spectrum is kept on each update while averaging
+static AVERAGE_COEFF: f32 = 0.8;
+
+// the level shown for silence, below the floor of every spectrum
+static SILENCE_DB: f32 = -144.0;
+
+/// The window applied to the samples before the FFT.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum FftWindow {
+    Rectangular,
+    Hann,
+    Hamming,
+    Blackman,
+}
+
+impl FftWindow {
+    pub(crate) fn name(self) -> &'static str {
+        match self {
+            FftWindow::Rectangular => "rect",
+            FftWindow::Hann => "hann",
+            FftWindow::Hamming => "hamming",
+            FftWindow::Blackman => "blackman",
+        }
+    }
+
+    pub(crate) fn next(self) -> Self {
+        match self {
+            FftWindow::Rectangular => FftWindow::Hann,
+            FftWindow::Hann => FftWindow::Hamming,
+            FftWindow::Hamming => FftWindow::Blackman,
+            FftWindow::Blackman => FftWindow::Rectangular,
+        }
+    }
+
+    // the coefficient at `i` of a window of `size` samples
+    fn coeff(self, i: usize, size: usize) -> f32 {
+        let phase = 2.0 * PI * i as f32 / size as f32;
+
+        match self {
+            FftWindow::Rectangular => 1.0,
+            FftWindow::Hann => 0.5 - 0.5 * phase.cos(),
+            FftWindow::Hamming => 0.54 - 0.46 * phase.cos(),
+            FftWindow::Blackman => 0.42 - 0.5 * phase.cos() + 0.08 * (2.0 * phase).cos(),
+        }
+    }
+}
+
+/// How successive spectrums are combined.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum SpectrumMode {
+    /// Only the latest spectrum is shown.
+    Instant,
+    /// The spectrum is smoothed over roughly the last few dozen updates.
+    Average,
+    /// The highest level of each bin is kept until the mode or the FFT
+    /// settings change.
+    PeakHold,
+}
+
+impl SpectrumMode {
+    pub(crate) fn name(self) -> &'static str {
+        match self {
+            SpectrumMode::Instant => "inst",
+            SpectrumMode::Average => "avg",
+            SpectrumMode::PeakHold => "peak",
+        }
+    }
+
+    pub(crate) fn next(self) -> Self {
+        match self {
+            SpectrumMode::Instant => SpectrumMode::Average,
+            SpectrumMode::Average => SpectrumMode::PeakHold,
+            SpectrumMode::PeakHold => SpectrumMode::Instant,
+        }
+    }
+}
+
+/// Turns the latest samples of a signal into the level of each frequency bin.
+/// It runs on the GUI thread, never on the audio thread.
+pub(crate) struct Analyzer {
+    fft: Arc<dyn FFT<f32>>,
+    window: Vec<f32>,
+    // scales the magnitudes so that a full scale sine shows at 0 dB
+    scale: f32,
+    input: Vec<Complex<f32>>,
+    output: Vec<Complex<f32>>,
+    // the combined power of each bin, from DC up to Nyquist
+    powers: Vec<f32>,
+    has_powers: bool,
+}
+
+impl Analyzer {
+    pub(crate) fn new(size: usize, window: FftWindow) -> Self {
+        let mut planner = FFTplanner::new(false);
+        let window: Vec<f32> = (0..size).map(|i| window.coeff(i, size)).collect();
+        let scale = 2.0 / window.iter().sum::<f32>();
+
+        Self {
+            fft: planner.plan_fft(size),
+            window,
+            scale,
+            input: vec![Complex::zero(); size],
+            output: vec![Complex::zero(); size],
+            powers: vec![0.0; size / 2 + 1],
+            has_powers: false,
+        }
+    }
+
+    pub(crate) fn size(&self) -> usize {
+        self.window.len()
+    }
+
+    /// Analyzes the latest samples, and combines the result with the previous
+    /// spectrum according to `mode`. Missing samples at the start count as
+    /// silence.
+    pub(crate) fn analyze(&mut self, samples: &[f32], mode: SpectrumMode) {
+        let offset = self.size().saturating_sub(samples.len());
+        for (i, (input, coeff)) in self.input.iter_mut().zip(self.window.iter()).enumerate() {
+            let sample = if i >= offset {
+                samples[i - offset]
+            } else {
+                0.0
+            };
+            *input = Complex::new(sample * coeff, 0.0);
+        }
+
+        self.fft.process(&mut self.input, &mut self.output);
+
+        for (power, bin) in self.powers.iter_mut().zip(self.output.iter()) {
+            let new_power = (bin.norm() * self.scale).powi(2);
+
+            *power = if !self.has_powers {
+                new_power
+            } else {
+                match mode {
+                    SpectrumMode::Instant => new_power,
+                    SpectrumMode::Average => {
+                        *power * AVERAGE_COEFF + new_power * (1.0 - AVERAGE_COEFF)
+                    }
+                    SpectrumMode::PeakHold => power.max(new_power),
+                }
+            };
+        }
+        self.has_powers = true;
+    }
+
+    /// Forgets the averaged or held spectrum.
+    pub(crate) fn clear(&mut self) {
+        self.has_powers = false;
+    }
+
+    /// The level of the bin in dBFS.
+    pub(crate) fn bin_db(&self, bin: usize) -> f32 {
+        let power = self.powers[bin];
+        if power > 0.0 {
+            (10.0 * power.log10()).max(SILENCE_DB)
+        } else {
+            SILENCE_DB
+        }
+    }
+
+    pub(crate) fn num_bins(&self) -> usize {
+        self.powers.len()
+    }
+}
@@ -1,6 +1,6 @@
-use iced::{button, container};
+use iced::{button, container, pick_list, radio, text_input, Color};
 
-use iced_audio::knob;
+use iced_audio::{h_slider, knob, v_slider, xy_pad};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Theme {
@@ -8,7 +8,30 @@ pub enum Theme {
     Dark,
 }
 
+/// The colors of plots drawn on a canvas, such as the scope.
+#[derive(Debug, Clone, Copy)]
+pub struct PlotStyle {
+    pub background: Color,
+    pub grid: Color,
+    /// The main trace, e.g. the output.
+    pub primary: Color,
+    /// A trace drawn for comparison, e.g. the input.
+    pub secondary: Color,
+}
+
 impl Theme {
+    pub fn plot(&self) -> PlotStyle {
+        match self {
+            Theme::_Light => PlotStyle {
+                background: Color::WHITE,
+                grid: Color::from_rgb(0.85, 0.85, 0.85),
+                primary: Color::from_rgb(0.2, 0.4, 0.8),
+                secondary: Color::from_rgb(0.9, 0.5, 0.1),
+            },
+            Theme::Dark => dark::PLOT,
+        }
+    }
+
     pub fn page_container(&self) -> Box<dyn container::StyleSheet> {
         match self {
             Theme::_Light => Default::default(),
@@ -23,6 +46,69 @@ impl Theme {
         }
     }
 
+    pub fn disabled_block_container(&self) -> Box<dyn container::StyleSheet> {
+        match self {
+            Theme::_Light => Default::default(),
+            Theme::Dark => dark::DisabledBlockContainer.into(),
+        }
+    }
+
+    pub fn mod_indicator(&self) -> Box<dyn container::StyleSheet> {
+        match self {
+            Theme::_Light => Default::default(),
+            Theme::Dark => dark::ModIndicator.into(),
+        }
+    }
+
+    pub fn meter_rms(&self) -> Box<dyn container::StyleSheet> {
+        match self {
+            Theme::_Light => Default::default(),
+            Theme::Dark => dark::MeterRms.into(),
+        }
+    }
+
+    pub fn meter_peak(&self) -> Box<dyn container::StyleSheet> {
+        match self {
+            Theme::_Light => Default::default(),
+            Theme::Dark => dark::MeterPeak.into(),
+        }
+    }
+
+    pub fn meter_hold(&self) -> Box<dyn container::StyleSheet> {
+        match self {
+            Theme::_Light => Default::default(),
+            Theme::Dark => dark::MeterHold.into(),
+        }
+    }
+
+    pub fn clip_button(&self) -> Box<dyn button::StyleSheet> {
+        match self {
+            Theme::_Light => Default::default(),
+            Theme::Dark => dark::ClipButton.into(),
+        }
+    }
+
+    pub fn clipped_button(&self) -> Box<dyn button::StyleSheet> {
+        match self {
+            Theme::_Light => Default::default(),
+            Theme::Dark => dark::ClippedButton.into(),
+        }
+    }
+
+    pub fn readout_bar(&self) -> Box<dyn container::StyleSheet> {
+        match self {
+            Theme::_Light => Default::default(),
+            Theme::Dark => dark::ReadoutBar.into(),
+        }
+    }
+
+    pub fn indicator_back(&self) -> Box<dyn container::StyleSheet> {
+        match self {
+            Theme::_Light => Default::default(),
+            Theme::Dark => dark::IndicatorBack.into(),
+        }
+    }
+
     pub fn button(&self) -> Box<dyn button::StyleSheet> {
         match self {
             Theme::_Light => Default::default(),
@@ -37,6 +123,20 @@ impl Theme {
         }
     }
 
+    pub fn toggle_on(&self) -> Box<dyn button::StyleSheet> {
+        match self {
+            Theme::_Light => Default::default(),
+            Theme::Dark => dark::ToggleOn.into(),
+        }
+    }
+
+    pub fn toggle_off(&self) -> Box<dyn button::StyleSheet> {
+        match self {
+            Theme::_Light => Default::default(),
+            Theme::Dark => dark::ToggleOff.into(),
+        }
+    }
+
     pub fn bypassed_button(&self) -> Box<dyn button::StyleSheet> {
         match self {
             Theme::_Light => Default::default(),
@@ -44,17 +144,117 @@ impl Theme {
         }
     }
 
+    pub fn value_text_button(&self) -> Box<dyn button::StyleSheet> {
+        match self {
+            Theme::_Light => Default::default(),
+            Theme::Dark => dark::ValueTextButton.into(),
+        }
+    }
+
+    pub fn text_input(&self) -> Box<dyn text_input::StyleSheet> {
+        match self {
+            Theme::_Light => Default::default(),
+            Theme::Dark => dark::TextInput.into(),
+        }
+    }
+
+    pub fn invalid_text_input(&self) -> Box<dyn text_input::StyleSheet> {
+        match self {
+            Theme::_Light => Default::default(),
+            Theme::Dark => dark::InvalidTextInput.into(),
+        }
+    }
+
     pub fn knob(&self) -> Box<dyn knob::StyleSheet> {
         match self {
             Theme::_Light => Default::default(),
             Theme::Dark => dark::Knob.into(),
         }
     }
+
+    pub fn disabled_knob(&self) -> Box<dyn knob::StyleSheet> {
+        match self {
+            Theme::_Light => Default::default(),
+            Theme::Dark => dark::DisabledKnob.into(),
+        }
+    }
+
+    pub fn h_slider(&self) -> Box<dyn h_slider::StyleSheet> {
+        match self {
+            Theme::_Light => Default::default(),
+            Theme::Dark => dark::HSlider.into(),
+        }
+    }
+
+    pub fn disabled_h_slider(&self) -> Box<dyn h_slider::StyleSheet> {
+        match self {
+            Theme::_Light => Default::default(),
+            Theme::Dark => dark::DisabledHSlider.into(),
+        }
+    }
+
+    pub fn v_slider(&self) -> Box<dyn v_slider::StyleSheet> {
+        match self {
+            Theme::_Light => Default::default(),
+            Theme::Dark => dark::VSlider.into(),
+        }
+    }
+
+    pub fn disabled_v_slider(&self) -> Box<dyn v_slider::StyleSheet> {
+        match self {
+            Theme::_Light => Default::default(),
+            Theme::Dark => dark::DisabledVSlider.into(),
+        }
+    }
+
+    pub fn pick_list(&self) -> Box<dyn pick_list::StyleSheet> {
+        match self {
+            Theme::_Light => Default::default(),
+            Theme::Dark => dark::PickList.into(),
+        }
+    }
+
+    pub fn disabled_pick_list(&self) -> Box<dyn pick_list::StyleSheet> {
+        match self {
+            Theme::_Light => Default::default(),
+            Theme::Dark => dark::DisabledPickList.into(),
+        }
+    }
+
+    pub fn radio(&self) -> Box<dyn radio::StyleSheet> {
+        match self {
+            Theme::_Light => Default::default(),
+            Theme::Dark => dark::Radio.into(),
+        }
+    }
+
+    pub fn disabled_radio(&self) -> Box<dyn radio::StyleSheet> {
+        match self {
+            Theme::_Light => Default::default(),
+            Theme::Dark => dark::DisabledRadio.into(),
+        }
+    }
+
+    pub fn xy_pad(&self) -> Box<dyn xy_pad::StyleSheet> {
+        match self {
+            Theme::_Light => Default::default(),
+            Theme::Dark => dark::XYPad.into(),
+        }
+    }
+
+    pub fn disabled_xy_pad(&self) -> Box<dyn xy_pad::StyleSheet> {
+        match self {
+            Theme::_Light => Default::default(),
+            Theme::Dark => dark::DisabledXYPad.into(),
+        }
+    }
 }
 
 mod dark {
-    use iced::{button, container, Background, Color};
-    use iced_audio::knob;
+    use iced::{button, container, pick_list, radio, text_input, Background, Color};
+    use iced_audio::{h_slider, knob, v_slider, xy_pad};
+
+    use super::PlotStyle;
 
     const BACKGROUND: Color = Color::from_rgb(
         0x20 as f32 / 255.0,
@@ -80,6 +280,12 @@ mod dark {
         0xE9 as f32 / 255.0,
     );
 
+    const MODULATION: Color = Color::from_rgb(
+        0xFF as f32 / 255.0,
+        0xB8 as f32 / 255.0,
+        0x6C as f32 / 255.0,
+    );
+
     const ACTIVE: Color = Color::from_rgb(
         0x72 as f32 / 255.0,
         0x89 as f32 / 255.0,
@@ -98,6 +304,13 @@ mod dark {
         0x7C as f32 / 255.0,
     );
 
+    pub const PLOT: PlotStyle = PlotStyle {
+        background: BACKGROUND,
+        grid: PANEL_BACKGROUND,
+        primary: ACCENT,
+        secondary: MODULATION,
+    };
+
     const BYPASS_ACTIVE: Color = Color::from_rgb(
         0xDA as f32 / 255.0,
         0x73 as f32 / 255.0,
@@ -132,6 +345,108 @@ mod dark {
         }
     }
 
+    pub struct DisabledBlockContainer;
+    impl container::StyleSheet for DisabledBlockContainer {
+        fn style(&self) -> container::Style {
+            container::Style {
+                background: Some(Background::Color(PANEL_BACKGROUND)),
+                text_color: Some(DISABLED),
+                ..container::Style::default()
+            }
+        }
+    }
+
+    pub struct ModIndicator;
+    impl container::StyleSheet for ModIndicator {
+        fn style(&self) -> container::Style {
+            container::Style {
+                background: Some(Background::Color(MODULATION)),
+                ..container::Style::default()
+            }
+        }
+    }
+
+    pub struct MeterRms;
+    impl container::StyleSheet for MeterRms {
+        fn style(&self) -> container::Style {
+            container::Style {
+                background: Some(Background::Color(ACCENT)),
+                ..container::Style::default()
+            }
+        }
+    }
+
+    pub struct MeterPeak;
+    impl container::StyleSheet for MeterPeak {
+        fn style(&self) -> container::Style {
+            container::Style {
+                background: Some(Background::Color(Color { a: 0.5, ..ACCENT })),
+                ..container::Style::default()
+            }
+        }
+    }
+
+    pub struct MeterHold;
+    impl container::StyleSheet for MeterHold {
+        fn style(&self) -> container::Style {
+            container::Style {
+                background: Some(Background::Color(Color::WHITE)),
+                ..container::Style::default()
+            }
+        }
+    }
+
+    pub struct ClipButton;
+    impl button::StyleSheet for ClipButton {
+        fn active(&self) -> button::Style {
+            button::Style {
+                background: Some(Background::Color(BACKGROUND)),
+                border_radius: 2,
+                text_color: DISABLED,
+                ..button::Style::default()
+            }
+        }
+    }
+
+    pub struct ClippedButton;
+    impl button::StyleSheet for ClippedButton {
+        fn active(&self) -> button::Style {
+            button::Style {
+                background: Some(Background::Color(BYPASS_ACTIVE)),
+                border_radius: 2,
+                text_color: Color::WHITE,
+                ..button::Style::default()
+            }
+        }
+
+        fn hovered(&self) -> button::Style {
+            button::Style {
+                background: Some(Background::Color(BYPASS_HOVERED)),
+                ..self.active()
+            }
+        }
+    }
+
+    pub struct ReadoutBar;
+    impl container::StyleSheet for ReadoutBar {
+        fn style(&self) -> container::Style {
+            container::Style {
+                background: Some(Background::Color(ACCENT)),
+                ..container::Style::default()
+            }
+        }
+    }
+
+    pub struct IndicatorBack;
+    impl container::StyleSheet for IndicatorBack {
+        fn style(&self) -> container::Style {
+            container::Style {
+                background: Some(Background::Color(EMPTY)),
+                ..container::Style::default()
+            }
+        }
+    }
+
     pub struct Knob;
     impl knob::StyleSheet for Knob {
         fn active(&self) -> knob::Style {
@@ -160,6 +475,130 @@ mod dark {
         }
     }
 
+    pub struct DisabledKnob;
+    impl knob::StyleSheet for DisabledKnob {
+        fn active(&self) -> knob::Style {
+            knob::Style::Arc(knob::ArcStyle {
+                width: 2.5,
+                empty_color: EMPTY,
+                filled_color: DISABLED,
+                notch: Some(knob::ArcNotch {
+                    width: 2.5,
+                    length_scale: 0.55.into(),
+                    color: DISABLED,
+                }),
+            })
+        }
+
+        fn hovered(&self) -> knob::Style {
+            self.active()
+        }
+
+        fn dragging(&self) -> knob::Style {
+            self.active()
+        }
+
+        fn angle_range(&self) -> iced_audio::KnobAngleRange {
+            iced_audio::KnobAngleRange::from_deg(40.0, 320.0)
+        }
+    }
+
+    pub struct HSlider;
+    impl h_slider::StyleSheet for HSlider {
+        fn active(&self) -> h_slider::Style {
+            h_slider::Style::Rect(h_slider::RectStyle {
+                back_color: EMPTY,
+                back_border_width: 1,
+                back_border_radius: 2,
+                back_border_color: BACKGROUND,
+                filled_color: ACCENT,
+                handle_width: 3,
+                handle_color: Color::WHITE,
+                handle_filled_gap: 1,
+            })
+        }
+
+        fn hovered(&self) -> h_slider::Style {
+            self.active()
+        }
+
+        fn dragging(&self) -> h_slider::Style {
+            self.active()
+        }
+    }
+
+    pub struct DisabledHSlider;
+    impl h_slider::StyleSheet for DisabledHSlider {
+        fn active(&self) -> h_slider::Style {
+            h_slider::Style::Rect(h_slider::RectStyle {
+                back_color: EMPTY,
+                back_border_width: 1,
+                back_border_radius: 2,
+                back_border_color: BACKGROUND,
+                filled_color: DISABLED,
+                handle_width: 3,
+                handle_color: DISABLED,
+                handle_filled_gap: 1,
+            })
+        }
+
+        fn hovered(&self) -> h_slider::Style {
+            self.active()
+        }
+
+        fn dragging(&self) -> h_slider::Style {
+            self.active()
+        }
+    }
+
+    pub struct VSlider;
+    impl v_slider::StyleSheet for VSlider {
+        fn active(&self) -> v_slider::Style {
+            v_slider::Style::Rect(v_slider::RectStyle {
+                back_color: EMPTY,
+                back_border_width: 1,
+                back_border_radius: 2,
+                back_border_color: BACKGROUND,
+                filled_color: ACCENT,
+                handle_height: 3,
+                handle_color: Color::WHITE,
+                handle_filled_gap: 1,
+            })
+        }
+
+        fn hovered(&self) -> v_slider::Style {
+            self.active()
+        }
+
+        fn dragging(&self) -> v_slider::Style {
+            self.active()
+        }
+    }
+
+    pub struct DisabledVSlider;
+    impl v_slider::StyleSheet for DisabledVSlider {
+        fn active(&self) -> v_slider::Style {
+            v_slider::Style::Rect(v_slider::RectStyle {
+                back_color: EMPTY,
+                back_border_width: 1,
+                back_border_radius: 2,
+                back_border_color: BACKGROUND,
+                filled_color: DISABLED,
+                handle_height: 3,
+                handle_color: DISABLED,
+                handle_filled_gap: 1,
+            })
+        }
+
+        fn hovered(&self) -> v_slider::Style {
+            self.active()
+        }
+
+        fn dragging(&self) -> v_slider::Style {
+            self.active()
+        }
+    }
+
     pub struct Button;
     impl button::StyleSheet for Button {
         fn active(&self) -> button::Style {
@@ -235,4 +674,274 @@ mod dark {
             }
         }
     }
+
+    pub struct ToggleOn;
+    impl button::StyleSheet for ToggleOn {
+        fn active(&self) -> button::Style {
+            button::Style {
+                background: Some(Background::Color(ACCENT)),
+                border_radius: 3,
+                text_color: BACKGROUND,
+                ..button::Style::default()
+            }
+        }
+
+        fn hovered(&self) -> button::Style {
+            button::Style {
+                border_width: 1,
+                border_color: Color::WHITE,
+                ..self.active()
+            }
+        }
+
+        fn pressed(&self) -> button::Style {
+            self.hovered()
+        }
+    }
+
+    pub struct ToggleOff;
+    impl button::StyleSheet for ToggleOff {
+        fn active(&self) -> button::Style {
+            button::Style {
+                background: Some(Background::Color(EMPTY)),
+                border_radius: 3,
+                text_color: Color::WHITE,
+                ..button::Style::default()
+            }
+        }
+
+        fn hovered(&self) -> button::Style {
+            button::Style {
+                border_width: 1,
+                border_color: Color::WHITE,
+                ..self.active()
+            }
+        }
+
+        fn pressed(&self) -> button::Style {
+            self.hovered()
+        }
+    }
+
+    pub struct ValueTextButton;
+    impl button::StyleSheet for ValueTextButton {
+        fn active(&self) -> button::Style {
+            button::Style {
+                background: None,
+                text_color: Color::WHITE,
+                ..button::Style::default()
+            }
+        }
+
+        fn hovered(&self) -> button::Style {
+            button::Style {
+                text_color: ACCENT,
+                ..self.active()
+            }
+        }
+
+        fn pressed(&self) -> button::Style {
+            self.hovered()
+        }
+    }
+
+    pub struct TextInput;
+    impl text_input::StyleSheet for TextInput {
+        fn active(&self) -> text_input::Style {
+            text_input::Style {
+                background: Background::Color(BACKGROUND),
+                border_radius: 2,
+                border_width: 1,
+                border_color: EMPTY,
+            }
+        }
+
+        fn focused(&self) -> text_input::Style {
+            text_input::Style {
+                border_color: ACCENT,
+                ..self.active()
+            }
+        }
+
+        fn placeholder_color(&self) -> Color {
+            DISABLED
+        }
+
+        fn value_color(&self) -> Color {
+            Color::WHITE
+        }
+
+        fn selection_color(&self) -> Color {
+            ACTIVE
+        }
+    }
+
+    pub struct InvalidTextInput;
+    impl text_input::StyleSheet for InvalidTextInput {
+        fn active(&self) -> text_input::Style {
+            text_input::Style {
+                border_color: BYPASS_ACTIVE,
+                ..TextInput.active()
+            }
+        }
+
+        fn focused(&self) -> text_input::Style {
+            self.active()
+        }
+
+        fn placeholder_color(&self) -> Color {
+            TextInput.placeholder_color()
+        }
+
+        fn value_color(&self) -> Color {
+            TextInput.value_color()
+        }
+
+        fn selection_color(&self) -> Color {
+            TextInput.selection_color()
+        }
+    }
+
+    pub struct PickList;
+    impl pick_list::StyleSheet for PickList {
+        fn menu(&self) -> pick_list::Menu {
+            pick_list::Menu {
+                text_color: Color::WHITE,
+                background: Background::Color(BACKGROUND),
+                border_width: 1,
+                border_color: EMPTY,
+                selected_text_color: BACKGROUND,
+                selected_background: Background::Color(ACCENT),
+            }
+        }
+
+        fn active(&self) -> pick_list::Style {
+            pick_list::Style {
+                text_color: Color::WHITE,
+                background: Background::Color(BACKGROUND),
+                border_radius: 2,
+                border_width: 1,
+                border_color: EMPTY,
+                icon_size: 0.7,
+            }
+        }
+
+        fn hovered(&self) -> pick_list::Style {
+            pick_list::Style {
+                border_color: ACCENT,
+                ..self.active()
+            }
+        }
+    }
+
+    pub struct DisabledPickList;
+    impl pick_list::StyleSheet for DisabledPickList {
+        fn menu(&self) -> pick_list::Menu {
+            PickList.menu()
+        }
+
+        fn active(&self) -> pick_list::Style {
+            pick_list::Style {
+                text_color: DISABLED,
+                ..PickList.active()
+            }
+        }
+
+        fn hovered(&self) -> pick_list::Style {
+            self.active()
+        }
+    }
+
+    pub struct Radio;
+    impl radio::StyleSheet for Radio {
+        fn active(&self) -> radio::Style {
+            radio::Style {
+                background: Background::Color(BACKGROUND),
+                dot_color: ACCENT,
+                border_width: 1,
+                border_color: EMPTY,
+            }
+        }
+
+        fn hovered(&self) -> radio::Style {
+            radio::Style {
+                border_color: ACCENT,
+                ..self.active()
+            }
+        }
+    }
+
+    pub struct DisabledRadio;
+    impl radio::StyleSheet for DisabledRadio {
+        fn active(&self) -> radio::Style {
+            radio::Style {
+                dot_color: DISABLED,
+                ..Radio.active()
+            }
+        }
+
+        fn hovered(&self) -> radio::Style {
+            self.active()
+        }
+    }
+
+    pub struct XYPad;
+    impl xy_pad::StyleSheet for XYPad {
+        fn active(&self) -> xy_pad::Style {
+            xy_pad::Style {
+                rail_width: 1,
+                h_rail_color: ACCENT,
+                v_rail_color: ACCENT,
+                handle: xy_pad::HandleShape::Circle(xy_pad::HandleCircle {
+                    color: Color::WHITE,
+                    diameter: 7,
+                    border_width: 1,
+                    border_color: BACKGROUND,
+                }),
+                back_color: EMPTY,
+                border_width: 1,
+                border_color: BACKGROUND,
+                center_line_width: 1,
+                center_line_color: PANEL_BACKGROUND,
+            }
+        }
+
+        fn hovered(&self) -> xy_pad::Style {
+            self.active()
+        }
+
+        fn dragging(&self) -> xy_pad::Style {
+            self.active()
+        }
+    }
+
+    pub struct DisabledXYPad;
+    impl xy_pad::StyleSheet for DisabledXYPad {
+        fn active(&self) -> xy_pad::Style {
+            xy_pad::Style {
+                rail_width: 1,
+                h_rail_color: DISABLED,
+                v_rail_color: DISABLED,
+                handle: xy_pad::HandleShape::Circle(xy_pad::HandleCircle {
+                    color: DISABLED,
+                    diameter: 7,
+                    border_width: 1,
+                    border_color: BACKGROUND,
+                }),
+                back_color: EMPTY,
+                border_width: 1,
+                border_color: BACKGROUND,
+                center_line_width: 1,
+                center_line_color: PANEL_BACKGROUND,
+            }
+        }
+
+        fn hovered(&self) -> xy_pad::Style {
+            self.active()
+        }
+
+        fn dragging(&self) -> xy_pad::Style {
+            self.active()
+        }
+    }
 }